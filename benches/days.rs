@@ -0,0 +1,122 @@
+//! Benchmarks parsing and each part of every day on its checked-in input.
+//!
+//! Run with `cargo bench`; criterion's own flags work as usual, e.g.
+//! `cargo bench -- --save-baseline before` and later `-- --baseline before`.
+//! Afterwards a summary of the mean time of every benchmark is printed and
+//! written to `target/criterion/summary.txt`, so it can be diffed between commits.
+//! Only benchmarks that ran this time are in it, so with a filter like
+//! `cargo bench -- day5` it leaves out the days that didn't run.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use advent_of_code_2020::DAYS;
+use criterion::{black_box, Criterion, SamplingMode};
+use serde_json::Value;
+
+static PHASES: [&str; 3] = ["parse", "part1", "part2"];
+
+// Parts slower than this are sampled less, so that `cargo bench` finishes in reasonable time.
+const SLOW: Duration = Duration::from_millis(50);
+
+fn main() {
+    let mut criterion = Criterion::default().configure_from_args();
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let started = SystemTime::now();
+
+    for day in DAYS {
+        let input = match fs::read_to_string(root.join(day.input_path())) {
+            Ok(input) => input,
+            Err(_) => continue,
+        };
+        let parsed = day.parse(&input).expect("checked-in input failed to parse");
+
+        let start = Instant::now();
+        let _ = parsed.part1();
+        if day.parts == 2 {
+            let _ = parsed.part2();
+        }
+        let slow = start.elapsed() > SLOW;
+
+        let mut group = criterion.benchmark_group(format!("day{}", day.day));
+        if slow {
+            group.sample_size(10).sampling_mode(SamplingMode::Flat);
+        }
+
+        group.bench_function("parse", |b| b.iter(|| day.parse(black_box(&input))));
+        group.bench_function("part1", |b| b.iter(|| parsed.part1()));
+        if day.parts == 2 {
+            group.bench_function("part2", |b| b.iter(|| parsed.part2()));
+        }
+        group.finish();
+    }
+
+    criterion.final_summary();
+    write_summary(&criterion_dir(root), started);
+}
+
+fn criterion_dir(root: &Path) -> PathBuf {
+    env::var_os("CARGO_TARGET_DIR")
+        .map_or_else(|| root.join("target"), PathBuf::from)
+        .join("criterion")
+}
+
+fn write_summary(dir: &Path, since: SystemTime) {
+    let mut summary = format!("{:<8}", "");
+    for phase in &PHASES {
+        summary.push_str(&format!("{:>14}", phase));
+    }
+    summary.push('\n');
+
+    for day in DAYS {
+        let group = format!("day{}", day.day);
+        let means: Vec<Option<f64>> = PHASES
+            .iter()
+            .map(|phase| mean_nanos(&dir.join(&group).join(phase), since))
+            .collect();
+
+        if means.iter().all(Option::is_none) {
+            continue;
+        }
+
+        summary.push_str(&format!("{:<8}", group));
+        for mean in means {
+            let mean = mean.map_or_else(|| "-".to_string(), format_nanos);
+            summary.push_str(&format!("{:>14}", mean));
+        }
+        summary.push('\n');
+    }
+
+    println!("\n{}", summary);
+    if let Err(err) = fs::write(dir.join("summary.txt"), &summary) {
+        eprintln!("could not write the benchmark summary: {}", err);
+    }
+}
+
+// Reads the mean of the latest run of a benchmark from criterion's estimates, if that run
+// was at `since` or later.
+fn mean_nanos(bench_dir: &Path, since: SystemTime) -> Option<f64> {
+    let path = bench_dir.join("new/estimates.json");
+    if fs::metadata(&path).and_then(|meta| meta.modified()).ok()? < since {
+        return None;
+    }
+
+    let estimates = fs::read_to_string(path).ok()?;
+    let estimates: Value = serde_json::from_str(&estimates).ok()?;
+
+    estimates["mean"]["point_estimate"].as_f64()
+}
+
+fn format_nanos(nanos: f64) -> String {
+    if nanos < 1e3 {
+        format!("{:.2} ns", nanos)
+    } else if nanos < 1e6 {
+        format!("{:.2} µs", nanos / 1e3)
+    } else if nanos < 1e9 {
+        format!("{:.2} ms", nanos / 1e6)
+    } else {
+        format!("{:.2} s", nanos / 1e9)
+    }
+}
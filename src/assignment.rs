@@ -0,0 +1,158 @@
+//! Matching every key to a different value from its candidates, when the candidates
+//! narrow down to a single way of doing it.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AssignError<K, V> {
+    /// Every candidate of `key` was taken by other keys, or has to be for the other keys to get
+    /// one each.
+    Contradiction { key: K },
+    /// The keys left over could each still be matched to more than one value.
+    Ambiguous {
+        unresolved: BTreeMap<K, BTreeSet<V>>,
+    },
+}
+
+impl<K: Display, V: Display> Display for AssignError<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssignError::Contradiction { key } => {
+                write!(f, "`{}` has no candidates left", key)
+            }
+            AssignError::Ambiguous { unresolved } => {
+                write!(f, "could not narrow down")?;
+                for (i, (key, values)) in unresolved.iter().enumerate() {
+                    let values: Vec<String> = values.iter().map(|v| format!("`{}`", v)).collect();
+                    let sep = if i == 0 { "" } else { ";" };
+                    write!(f, "{} `{}` (any of {})", sep, key, values.join(", "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<K: Display + Debug, V: Display + Debug> Error for AssignError<K, V> {}
+
+/// Repeatedly matches a key with only one candidate left to it, and takes that value away from
+/// every other key, until every key is matched or no key has exactly one candidate. The keys
+/// left over are only ambiguous if they can all be matched at once.
+pub fn solve<K, V>(
+    mut candidates: BTreeMap<K, BTreeSet<V>>,
+) -> Result<BTreeMap<K, V>, AssignError<K, V>>
+where
+    K: Ord + Clone,
+    V: Ord + Clone,
+{
+    let mut assigned = BTreeMap::new();
+
+    while !candidates.is_empty() {
+        if let Some((key, _)) = candidates.iter().find(|(_, values)| values.is_empty()) {
+            return Err(AssignError::Contradiction { key: key.clone() });
+        }
+
+        let (key, value) = match candidates.iter().find(|(_, values)| values.len() == 1) {
+            Some((key, values)) => (key.clone(), values.iter().next().unwrap().clone()),
+            None => {
+                return Err(match unmatched_key(&candidates) {
+                    Some(key) => AssignError::Contradiction { key: key.clone() },
+                    None => AssignError::Ambiguous {
+                        unresolved: candidates,
+                    },
+                })
+            }
+        };
+
+        candidates.remove(&key);
+        for values in candidates.values_mut() {
+            values.remove(&value);
+        }
+        assigned.insert(key, value);
+    }
+
+    Ok(assigned)
+}
+
+// A key left out of a largest matching, found by growing the matching one augmenting path at a
+// time, if there is one.
+fn unmatched_key<K: Ord, V: Ord>(candidates: &BTreeMap<K, BTreeSet<V>>) -> Option<&K> {
+    let mut owners = BTreeMap::new();
+    candidates
+        .keys()
+        .find(|&key| !augment(key, candidates, &mut owners, &mut BTreeSet::new()))
+}
+
+// Tries to match `key`, moving keys that already have a value on to other values to make room.
+fn augment<'a, K: Ord, V: Ord>(
+    key: &'a K,
+    candidates: &'a BTreeMap<K, BTreeSet<V>>,
+    owners: &mut BTreeMap<&'a V, &'a K>,
+    visited: &mut BTreeSet<&'a V>,
+) -> bool {
+    for value in &candidates[key] {
+        if !visited.insert(value) {
+            continue;
+        }
+        let free = match owners.get(value) {
+            Some(&owner) => augment(owner, candidates, owners, visited),
+            None => true,
+        };
+        if free {
+            owners.insert(value, key);
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(list: &[(u8, &[char])]) -> BTreeMap<u8, BTreeSet<char>> {
+        list.iter()
+            .map(|&(key, values)| (key, values.iter().copied().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_solve() {
+        let solved = solve(candidates(&[
+            (1, &['a', 'b']),
+            (2, &['b']),
+            (3, &['a', 'b', 'c']),
+        ]));
+        let expected: BTreeMap<u8, char> = [(1, 'a'), (2, 'b'), (3, 'c')].iter().copied().collect();
+        assert_eq!(solved, Ok(expected));
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = solve(candidates(&[(1, &['a']), (2, &['a'])])).unwrap_err();
+        assert_eq!(err, AssignError::Contradiction { key: 2 });
+        assert_eq!(err.to_string(), "`2` has no candidates left");
+
+        // nobody is down to nothing, but three keys can't share two values
+        let err = solve(candidates(&[
+            (1, &['a', 'b']),
+            (2, &['a', 'b']),
+            (3, &['a', 'b']),
+        ]))
+        .unwrap_err();
+        assert_eq!(err, AssignError::Contradiction { key: 3 });
+
+        let err = solve(candidates(&[
+            (1, &['a', 'b']),
+            (2, &['a', 'b']),
+            (3, &['c']),
+        ]))
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "could not narrow down `1` (any of `a`, `b`); `2` (any of `a`, `b`)"
+        );
+    }
+}
@@ -0,0 +1,319 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::panic;
+use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::Instant;
+
+use advent_of_code_2020::gen::{self, Params};
+use advent_of_code_2020::runner::{self, DayReport};
+use advent_of_code_2020::status;
+use advent_of_code_2020::{Day, DAYS};
+
+static USAGE: &str = "\
+usage: aoc2020 --day <n> [--part <1|2>] [--input <file|->]
+       aoc2020 --all
+       aoc2020 --parallel [--jobs <n>]
+       aoc2020 gen --day <n> [--seed <n>] [--size <n>] [--width <n>] [--depth <n>]
+                   [--valid-rate <r>]
+       aoc2020 status
+
+options:
+    -d, --day <n>       the day to run
+    -p, --part <1|2>    run only this part (default: both)
+    -i, --input <path>  read the input from this file, or stdin if `-`
+                        (default: input/2020/day<n>.txt)
+    -a, --all           run every implemented day in order
+    -P, --parallel      run every implemented day on a pool of threads, then print
+                        a table of all answers and timings
+                        (both skip days without an input in input/2020)
+    -j, --jobs <n>      how many threads to use with `--parallel`
+                        (default: one per core)
+    -f, --format <f>    print the results as `text` (default) or `json`, with one
+                        record per part
+        --json <path>   also write the results as JSON to this file
+    -h, --help          print this message
+
+gen prints a synthetic input for a day to stdout:
+    --seed <n>          the random seed (default: 2020)
+    --size <n>          how many records to generate (default: 100)
+    --width <n>         grid columns for days 3 and 11, ticket fields for day 16,
+                        the side of the starting slice for day 17 and allergens
+                        for day 21 (default: 31)
+    --depth <n>         layers of bags for day 7, of parentheses for day 18 and of
+                        rules below rules 42 and 31 for day 19 (default: 6)
+    --valid-rate <r>    fraction of valid records for days 2, 4, 16 and 19
+                        (default: 0.5)
+
+status lists which parts of each day are implemented, and fails if any are missing.";
+
+#[derive(Default)]
+struct Options {
+    day: Option<u8>,
+    part: Option<u8>,
+    input: Option<String>,
+    all: bool,
+    parallel: bool,
+    jobs: Option<usize>,
+    json: bool,
+    json_path: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("`{}` needs a value", name))
+        };
+
+        match arg.as_str() {
+            "-d" | "--day" => {
+                let day = value(&arg)?;
+                options.day = Some(day.parse().map_err(|_| format!("invalid day `{}`", day))?);
+            }
+            "-p" | "--part" => {
+                options.part = Some(match value(&arg)?.as_str() {
+                    "1" => 1,
+                    "2" => 2,
+                    other => return Err(format!("invalid part `{}`", other)),
+                });
+            }
+            "-i" | "--input" => options.input = Some(value(&arg)?),
+            "-a" | "--all" => options.all = true,
+            "-P" | "--parallel" => options.parallel = true,
+            "-j" | "--jobs" => {
+                let jobs = value(&arg)?;
+                options.jobs = match jobs.parse() {
+                    Ok(0) | Err(_) => return Err(format!("invalid number of jobs `{}`", jobs)),
+                    Ok(jobs) => Some(jobs),
+                };
+            }
+            "-f" | "--format" => {
+                options.json = match value(&arg)?.as_str() {
+                    "text" => false,
+                    "json" => true,
+                    other => return Err(format!("invalid format `{}`", other)),
+                };
+            }
+            "--json" => options.json_path = Some(value(&arg)?),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            other => return Err(format!("unexpected argument `{}`", other)),
+        }
+    }
+
+    if options.all && (options.day.is_some() || options.input.is_some()) {
+        return Err("`--all` cannot be combined with `--day` or `--input`".to_string());
+    } else if options.parallel && (options.all || options.day.is_some() || options.input.is_some())
+    {
+        return Err(
+            "`--parallel` cannot be combined with `--all`, `--day` or `--input`".to_string(),
+        );
+    } else if !options.parallel && options.jobs.is_some() {
+        return Err("`--jobs` only works with `--parallel`".to_string());
+    } else if !options.all && !options.parallel && options.day.is_none() {
+        return Err("one of `--day`, `--all` or `--parallel` is required".to_string());
+    }
+
+    Ok(options)
+}
+
+fn parse_gen_args(mut args: impl Iterator<Item = String>) -> Result<(u8, Params), String> {
+    let mut day = None;
+    let mut params = Params::default();
+
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("`{}` needs a value", arg))?;
+        let invalid = || format!("invalid value `{}` for `{}`", value, arg);
+
+        match arg.as_str() {
+            "-d" | "--day" => day = Some(value.parse().map_err(|_| invalid())?),
+            "--seed" => params.seed = value.parse().map_err(|_| invalid())?,
+            "--size" => params.size = value.parse().map_err(|_| invalid())?,
+            "--width" => params.width = value.parse().map_err(|_| invalid())?,
+            "--depth" => params.depth = value.parse().map_err(|_| invalid())?,
+            "--valid-rate" => {
+                params.valid_rate = value.parse().map_err(|_| invalid())?;
+                if !(0.0..=1.0).contains(&params.valid_rate) {
+                    return Err("`--valid-rate` must be between 0 and 1".to_string());
+                }
+            }
+            other => return Err(format!("unexpected argument `{}`", other)),
+        }
+    }
+
+    let day = day.ok_or_else(|| "`gen` needs `--day`".to_string())?;
+    Ok((day, params))
+}
+
+fn main() {
+    let mut args = env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("gen") {
+        args.next();
+        let (day, params) = parse_gen_args(args).unwrap_or_else(|msg| {
+            eprintln!("error: {}\n\n{}", msg, USAGE);
+            process::exit(2);
+        });
+
+        match gen::generate(day, &params) {
+            Some(input) => print!("{}", input),
+            None => {
+                eprintln!("error: there is no generator for day {}", day);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if args.peek().map(String::as_str) == Some("status") {
+        args.next();
+        if let Some(arg) = args.next() {
+            eprintln!("error: unexpected argument `{}`\n\n{}", arg, USAGE);
+            process::exit(2);
+        }
+
+        let status = status::status(DAYS);
+        println!("{}", status::table(&status));
+        if !status.iter().all(|day| day.is_complete()) {
+            process::exit(1);
+        }
+        return;
+    }
+
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("error: {}\n\n{}", msg, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let parts: &[u8] = match options.part {
+        Some(1) => &[1],
+        Some(_) => &[2],
+        None => &[1, 2],
+    };
+
+    let reports = if options.parallel {
+        run_parallel(&days_with_inputs(), parts, options.jobs, options.json)
+    } else if options.all {
+        days_with_inputs()
+            .iter()
+            .map(|day| run_day(day, parts, None, options.json))
+            .collect()
+    } else {
+        let number = options.day.unwrap();
+        let day = match DAYS.iter().find(|day| day.day == number) {
+            Some(day) => day,
+            None => {
+                eprintln!("error: day {} is not implemented", number);
+                process::exit(1);
+            }
+        };
+        if let Some(part) = options.part.filter(|&part| part > day.parts) {
+            eprintln!("error: day {} has no part {}", number, part);
+            process::exit(1);
+        }
+        vec![run_day(day, parts, options.input.as_deref(), options.json)]
+    };
+
+    let mut success = reports.iter().all(DayReport::is_ok);
+    if options.json {
+        println!("{}", runner::to_json(&reports));
+    }
+    if let Some(path) = &options.json_path {
+        if let Err(err) = fs::write(path, runner::to_json(&reports)) {
+            eprintln!("error: could not write `{}`: {}", path, err);
+            success = false;
+        }
+    }
+
+    if !success {
+        process::exit(1);
+    }
+}
+
+fn run_day(day: &Day, parts: &[u8], path: Option<&str>, quiet: bool) -> DayReport {
+    let path = path.map_or_else(|| day.input_path(), str::to_string);
+    let input =
+        read_input(&path).map_err(|err| io::Error::new(err.kind(), format!("`{}`: {}", path, err)));
+    let report = runner::run_day(day, input, parts);
+    if quiet {
+        return report;
+    }
+
+    match &report.parse {
+        Ok(time) => println!("Day {} (parsed in {:?})", day.day, time),
+        Err(err) => eprintln!("error: {}", err),
+    }
+    for part in &report.parts {
+        match &part.answer {
+            Ok(answer) => println!("  Part {}: {} ({:?})", part.part, answer, part.time),
+            Err(err) => eprintln!("error: day {} part {}: {}", day.day, part.part, err),
+        }
+    }
+
+    report
+}
+
+// The days that have an input in the default place. The rest are skipped, with a note on
+// stderr, rather than counted as failures.
+fn days_with_inputs() -> Vec<Day> {
+    let (days, skipped): (Vec<Day>, Vec<Day>) = DAYS
+        .iter()
+        .partition(|day| Path::new(&day.input_path()).exists());
+
+    if !skipped.is_empty() {
+        let numbers: Vec<String> = skipped.iter().map(|day| day.day.to_string()).collect();
+        eprintln!(
+            "skipping day(s) {}, which have no input in input/2020",
+            numbers.join(", ")
+        );
+    }
+    days
+}
+
+fn run_parallel(days: &[Day], parts: &[u8], jobs: Option<usize>, quiet: bool) -> Vec<DayReport> {
+    let threads = jobs
+        .or_else(|| thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1);
+
+    // the panics are reported along with everything else, not as they happen
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let start = Instant::now();
+    let reports = runner::run_parallel(days, parts, threads, |day| {
+        fs::read_to_string(day.input_path())
+    });
+
+    panic::set_hook(hook);
+
+    if !quiet {
+        print!("{}", runner::table(&reports));
+        println!(
+            "wall time: {:.2?} on {} thread(s)",
+            start.elapsed(),
+            threads
+        );
+    }
+    reports
+}
+
+fn read_input(path: &str) -> io::Result<String> {
+    if path == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(path)
+    }
+}
@@ -1,52 +1,623 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+
 use aoc_runner_derive::*;
-use std::collections::HashMap;
+
+use crate::error::{self, Line, Origin, ParseError};
+use crate::solution::Solution;
 
 #[aoc_generator(day1)]
-fn generate(input: &str) -> Vec<u16> {
-    input
-        .lines()
-        .map(|x| u16::from_str_radix(x, 10).unwrap())
+pub fn parse(input: &str) -> Result<Vec<u64>, ParseError> {
+    error::lines(1, input)
+        .map(|line| line.parse(line.text, "an expense entry"))
         .collect()
 }
 
-#[aoc(day1, part1)]
-fn solve_part1(vec: &[u16]) -> u32 {
-    let map: HashMap<u16, u16> = vec
-        .iter()
-        .map(|x| (*x, 2020u16.checked_sub(*x)))
-        .filter(|(_, y)| y.is_some())
-        .map(|(x, y)| (y.unwrap(), x))
-        .collect();
+/// Entries that add up to the target, by index in ascending order and by value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    pub indices: Vec<usize>,
+    pub values: Vec<u64>,
+}
+
+impl Match {
+    pub fn product(&self) -> u64 {
+        self.values.iter().product()
+    }
+}
+
+/// Writes the values as a sum, followed by the lines they are on.
+impl fmt::Display for Match {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let values: Vec<String> = self.values.iter().map(u64::to_string).collect();
+        let lines: Vec<String> = self.indices.iter().map(|i| (i + 1).to_string()).collect();
+        write!(f, "{} (lines {})", values.join(" + "), lines.join(", "))
+    }
+}
+
+/// Every way of picking `k` entries that add up to `target`, for telling apart inputs with
+/// exactly one answer from those with none or several.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    pub k: usize,
+    pub target: u64,
+    pub matches: Vec<Match>,
+}
+
+impl Report {
+    pub fn new(values: &[u64], k: usize, target: u64) -> Report {
+        Report {
+            k,
+            target,
+            matches: k_sum_all(values, k, target),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// The answer, if there is exactly one.
+    pub fn unique(&self) -> Option<&Match> {
+        match &self.matches[..] {
+            [only] => Some(only),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.count() {
+            0 => write!(f, "no {} entries add up to {}", self.k, self.target),
+            1 => write!(f, "{} = {}", self.matches[0], self.target),
+            n => {
+                write!(
+                    f,
+                    "{} ways for {} entries to add up to {}:",
+                    n, self.k, self.target
+                )?;
+                for m in &self.matches {
+                    write!(f, "\n    {}", m)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Some `k` different entries of `values` that add up to `target`, if there are any.
+pub fn k_sum(values: &[u64], k: usize, target: u64) -> Option<Match> {
+    Search::new(values, true).run(k, target).pop()
+}
+
+/// Every set of `k` different entries of `values` that add up to `target`, ordered by their
+/// indices.
+pub fn k_sum_all(values: &[u64], k: usize, target: u64) -> Vec<Match> {
+    Search::new(values, false).run(k, target)
+}
+
+struct Search<'a> {
+    values: &'a [u64],
+    // indices into `values`, by increasing value; the search works on positions in this
+    order: Vec<usize>,
+    first_only: bool,
+    found: Vec<Vec<usize>>,
+}
+
+impl<'a> Search<'a> {
+    fn new(values: &'a [u64], first_only: bool) -> Search<'a> {
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by_key(|&i| values[i]);
+
+        Search {
+            values,
+            order,
+            first_only,
+            found: Vec::new(),
+        }
+    }
+
+    // Up to three entries, fixing all but the last two and finding those with two pointers,
+    // which takes O(n^(k-1)) time. Beyond that, the entries are split into two halves whose
+    // sums are matched up, which takes O(n^ceil(k/2)) time and space instead.
+    fn run(mut self, k: usize, target: u64) -> Vec<Match> {
+        if k <= 3 {
+            self.fix(&mut Vec::new(), 0, k, target);
+        } else {
+            self.meet_in_the_middle(k, target);
+        }
+
+        let mut matches: Vec<Match> = self
+            .found
+            .iter()
+            .map(|positions| {
+                let mut indices: Vec<usize> = positions.iter().map(|&p| self.order[p]).collect();
+                indices.sort_unstable();
+                let values = indices.iter().map(|&i| self.values[i]).collect();
+                Match { indices, values }
+            })
+            .collect();
+        matches.sort_by(|a, b| a.indices.cmp(&b.indices));
+        matches
+    }
 
-    for &x in vec {
-        match map.get(&x) {
-            Some(&y) => return (x as u32) * (y as u32),
-            None => continue,
+    fn value(&self, pos: usize) -> u64 {
+        self.values[self.order[pos]]
+    }
+
+    fn done(&self) -> bool {
+        self.first_only && !self.found.is_empty()
+    }
+
+    fn record(&mut self, prefix: &[usize], rest: &[usize]) {
+        if !self.done() {
+            self.found
+                .push(prefix.iter().chain(rest).copied().collect());
+        }
+    }
+
+    // Every way to add `k` positions from `start` on to `prefix`, adding up to `target`.
+    fn fix(&mut self, prefix: &mut Vec<usize>, start: usize, k: usize, target: u64) {
+        match k {
+            0 if target == 0 => self.record(prefix, &[]),
+            0 => {}
+            1 => {
+                let from =
+                    start + self.order[start..].partition_point(|&i| self.values[i] < target);
+                let to = from + self.order[from..].partition_point(|&i| self.values[i] == target);
+                for pos in from..to {
+                    self.record(prefix, &[pos]);
+                }
+            }
+            2 => self.two_pointers(prefix, start, target),
+            _ => {
+                for pos in start..self.order.len() {
+                    let value = self.value(pos);
+                    if value > target || self.done() {
+                        break;
+                    }
+
+                    prefix.push(pos);
+                    self.fix(prefix, pos + 1, k - 1, target - value);
+                    prefix.pop();
+                }
+            }
+        }
+    }
+
+    fn two_pointers(&mut self, prefix: &[usize], start: usize, target: u64) {
+        let (mut lo, mut hi) = (start, self.order.len());
+
+        while lo + 1 < hi && !self.done() {
+            let (a, b) = (self.value(lo), self.value(hi - 1));
+            match cmp_sum(a, b, target) {
+                Ordering::Less => lo += 1,
+                Ordering::Greater => hi -= 1,
+                Ordering::Equal if a == b => {
+                    // everything in between is the same value, so any two of them will do
+                    for i in lo..hi {
+                        for j in i + 1..hi {
+                            self.record(prefix, &[i, j]);
+                        }
+                    }
+                    return;
+                }
+                Ordering::Equal => {
+                    let lo_end = (lo..hi).find(|&p| self.value(p) != a).unwrap();
+                    let hi_start = (lo..hi).rev().find(|&p| self.value(p) != b).unwrap() + 1;
+                    for i in lo..lo_end {
+                        for j in hi_start..hi {
+                            self.record(prefix, &[i, j]);
+                        }
+                    }
+                    lo = lo_end;
+                    hi = hi_start;
+                }
+            }
+        }
+    }
+
+    // Every `k` positions from `start` on that add up to at most `budget`, with their sums.
+    fn combinations(
+        &self,
+        prefix: &mut Vec<usize>,
+        start: usize,
+        k: usize,
+        sum: u64,
+        budget: u64,
+        out: &mut Vec<(u64, Vec<usize>)>,
+    ) {
+        if k == 0 {
+            out.push((sum, prefix.clone()));
+            return;
+        }
+
+        for pos in start..self.order.len() {
+            let value = self.value(pos);
+            if value > budget - sum {
+                break;
+            }
+
+            prefix.push(pos);
+            self.combinations(prefix, pos + 1, k - 1, sum + value, budget, out);
+            prefix.pop();
+        }
+    }
+
+    // Each set of positions is split into its lowest `k / 2` and the rest, so a half from the
+    // left only goes with a half from the right that starts after it ends.
+    fn meet_in_the_middle(&mut self, k: usize, target: u64) {
+        let mut left = Vec::new();
+        self.combinations(&mut Vec::new(), 0, k / 2, 0, target, &mut left);
+        let mut right = Vec::new();
+        self.combinations(&mut Vec::new(), 0, k - k / 2, 0, target, &mut right);
+
+        left.sort_unstable();
+        right.sort_unstable();
+
+        let (mut l, mut r) = (0, right.len());
+        while l < left.len() && r > 0 && !self.done() {
+            let (a, b) = (left[l].0, right[r - 1].0);
+            match cmp_sum(a, b, target) {
+                Ordering::Less => l += 1,
+                Ordering::Greater => r -= 1,
+                Ordering::Equal => {
+                    let l_end = l + left[l..].iter().take_while(|(sum, _)| *sum == a).count();
+                    let r_start = r - right[..r]
+                        .iter()
+                        .rev()
+                        .take_while(|(sum, _)| *sum == b)
+                        .count();
+
+                    for (_, low) in &left[l..l_end] {
+                        for (_, high) in &right[r_start..r] {
+                            if low.last() < high.first() {
+                                self.record(low, high);
+                            }
+                        }
+                    }
+                    l = l_end;
+                    r = r_start;
+                }
+            }
         }
     }
-    unreachable!()
+}
+
+// How `a + b` compares to `target`, without overflowing.
+fn cmp_sum(a: u64, b: u64, target: u64) -> Ordering {
+    (u128::from(a) + u128::from(b)).cmp(&u128::from(target))
+}
+
+/// Finds a pair and a triple of entries adding up to a target, taking the entries one at a time
+/// and keeping only what could still be part of an answer.
+#[derive(Clone, Debug)]
+pub struct Checker {
+    target: u64,
+    entries: usize,
+    // the index of the first entry with each value
+    values: HashMap<u64, usize>,
+    // the first two entries found adding up to each sum, by index and value
+    pair_sums: HashMap<u64, [(usize, u64); 2]>,
+    pair: Option<Match>,
+    triple: Option<Match>,
+}
+
+impl Checker {
+    pub fn new(target: u64) -> Checker {
+        Checker {
+            target,
+            entries: 0,
+            values: HashMap::new(),
+            pair_sums: HashMap::new(),
+            pair: None,
+            triple: None,
+        }
+    }
+
+    pub fn pair(&self) -> Option<&Match> {
+        self.pair.as_ref()
+    }
+
+    pub fn triple(&self) -> Option<&Match> {
+        self.triple.as_ref()
+    }
+
+    /// Whether both answers have been found, so that there is no point going on.
+    pub fn is_done(&self) -> bool {
+        self.pair.is_some() && self.triple.is_some()
+    }
+
+    /// Takes the next entry, returning the answers it completes.
+    pub fn push(&mut self, value: u64) -> Vec<Match> {
+        let index = self.entries;
+        self.entries += 1;
+
+        let mut found = Vec::new();
+        if value > self.target || self.is_done() {
+            return found;
+        }
+        let rest = self.target - value;
+
+        if self.pair.is_none() {
+            if let Some(&i) = self.values.get(&rest) {
+                found.push(Match {
+                    indices: vec![i, index],
+                    values: vec![rest, value],
+                });
+                self.pair = found.last().cloned();
+            }
+        }
+
+        if self.triple.is_none() {
+            if let Some(&[(i, a), (j, b)]) = self.pair_sums.get(&rest) {
+                found.push(Match {
+                    indices: vec![i, j, index],
+                    values: vec![a, b, value],
+                });
+                self.triple = found.last().cloned();
+                self.pair_sums = HashMap::new();
+            } else {
+                for (&v, &i) in &self.values {
+                    if v <= rest {
+                        self.pair_sums
+                            .entry(v + value)
+                            .or_insert([(i, v), (index, value)]);
+                    }
+                }
+            }
+        }
+
+        if self.is_done() {
+            self.values = HashMap::new();
+        } else {
+            self.values.entry(value).or_insert(index);
+        }
+        found
+    }
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StreamError::Io(err) => write!(f, "could not read the entries: {}", err),
+            StreamError::Parse(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(err: io::Error) -> StreamError {
+        StreamError::Io(err)
+    }
+}
+
+impl From<ParseError> for StreamError {
+    fn from(err: ParseError) -> StreamError {
+        StreamError::Parse(err)
+    }
+}
+
+/// Reads entries from `reader` a line at a time, yielding the pair and the triple adding up to
+/// `target` as soon as the entries that complete them are read. Stops reading once it has both.
+pub fn watch<R: BufRead>(reader: R, target: u64) -> Watch<R> {
+    Watch {
+        lines: reader.lines(),
+        number: 0,
+        checker: Checker::new(target),
+        found: VecDeque::new(),
+    }
+}
+
+pub struct Watch<R> {
+    lines: io::Lines<R>,
+    number: usize,
+    checker: Checker,
+    found: VecDeque<Match>,
+}
+
+impl<R> Watch<R> {
+    pub fn checker(&self) -> &Checker {
+        &self.checker
+    }
+}
+
+impl<R: BufRead> Iterator for Watch<R> {
+    type Item = Result<Match, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(found) = self.found.pop_front() {
+                return Some(Ok(found));
+            } else if self.checker.is_done() {
+                return None;
+            }
+
+            let text = match self.lines.next()? {
+                Ok(text) => text,
+                Err(err) => return Some(Err(err.into())),
+            };
+            self.number += 1;
+
+            let line = Line {
+                origin: Origin::Day(1),
+                number: self.number,
+                text: &text,
+            };
+            match line.parse(line.text, "an expense entry") {
+                Ok(value) => self.found.extend(self.checker.push(value)),
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+    }
+}
+
+#[aoc(day1, part1)]
+pub fn part1(input: &[u64]) -> u64 {
+    k_sum(input, 2, 2020)
+        .expect("no two entries add up to 2020")
+        .product()
 }
 
 #[aoc(day1, part2)]
-fn solve_part2(vec: &[u16]) -> u32 {
-    // sorry part 1, gonna have to go O(n^2) in this
-    let map: HashMap<u16, u16> = vec
-        .iter()
-        .map(|x| (*x, 2020u16.checked_sub(*x)))
-        .filter(|(_, y)| y.is_some())
-        .map(|(x, y)| (y.unwrap(), x))
-        .collect();
+pub fn part2(input: &[u64]) -> u64 {
+    k_sum(input, 3, 2020)
+        .expect("no three entries add up to 2020")
+        .product()
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<u64>;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::Rng;
+
+    static EXAMPLE: &str = "\
+1721
+979
+366
+299
+675
+1456";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), 514579);
+        assert_eq!(part2(&input), 241861950);
+
+        let pair = k_sum(&input, 2, 2020).unwrap();
+        assert_eq!((pair.indices, pair.values), (vec![0, 3], vec![1721, 299]));
+    }
+
+    #[test]
+    fn test_repeated_values() {
+        let matches = k_sum_all(&[1, 1, 2, 1], 2, 2);
+        let indices: Vec<Vec<usize>> = matches.into_iter().map(|m| m.indices).collect();
+        assert_eq!(indices, [[0, 1], [0, 3], [1, 3]]);
+
+        // a lone 1010 can't be used twice
+        assert_eq!(k_sum(&[1010, 3, 5], 2, 2020), None);
+    }
+
+    #[test]
+    fn test_report() {
+        let report = Report::new(&parse(EXAMPLE).unwrap(), 2, 2020);
+        assert_eq!(report.unique(), report.matches.first());
+        assert_eq!(report.to_string(), "1721 + 299 (lines 1, 4) = 2020");
+
+        let report = Report::new(&[1010, 7, 1010, 2013], 2, 2020);
+        assert_eq!(report.count(), 2);
+        assert_eq!(report.unique(), None);
+        assert_eq!(
+            report.to_string(),
+            "2 ways for 2 entries to add up to 2020:\n    \
+             1010 + 1010 (lines 1, 3)\n    \
+             7 + 2013 (lines 2, 4)"
+        );
+
+        let report = Report::new(&[1, 2], 3, 3);
+        assert_eq!(report.count(), 0);
+        assert_eq!(report.to_string(), "no 3 entries add up to 3");
+    }
+
+    #[test]
+    fn test_watch() {
+        // anything after the answers is never read
+        let stream = format!("{}\nnot a number", EXAMPLE);
+        let mut watch = watch(stream.as_bytes(), 2020);
+
+        let pair = watch.next().unwrap().unwrap();
+        assert_eq!(pair.indices, [0, 3]);
+        let triple = watch.next().unwrap().unwrap();
+        assert_eq!(triple.indices, [1, 2, 4]);
+        assert_eq!(triple.product(), 241861950);
+        assert!(watch.next().is_none());
+        assert!(watch.checker().is_done());
+
+        let errors: Vec<String> = super::watch("12\n1x\n".as_bytes(), 20)
+            .map(|found| found.unwrap_err().to_string())
+            .collect();
+        assert_eq!(
+            errors,
+            ["day 1, line 2, column 1: expected an expense entry, got `1x`"]
+        );
+    }
+
+    #[test]
+    fn test_checker_target() {
+        let mut checker = Checker::new(10);
+        let found: Vec<Vec<u64>> = [5, 11, 3, 2, 5]
+            .iter()
+            .flat_map(|&value| checker.push(value))
+            .map(|m| m.values)
+            .collect();
+        assert_eq!(found, [vec![5, 3, 2], vec![5, 5]]);
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        let mut rng = Rng::new(1);
+        for _ in 0..50 {
+            let values: Vec<u64> = (0..12).map(|_| rng.below(20)).collect();
+            let target = rng.below(60);
+
+            for k in 0..=6 {
+                let expected: Vec<Vec<usize>> = (0u32..1 << values.len())
+                    .filter(|mask| mask.count_ones() as usize == k)
+                    .map(|mask| (0..values.len()).filter(|i| mask & 1 << i != 0).collect())
+                    .filter(|indices: &Vec<usize>| {
+                        indices.iter().map(|&i| values[i]).sum::<u64>() == target
+                    })
+                    .collect();
 
-    let n = vec.len();
-    for i in 0..n {
-        let x = vec[i];
+                let all = k_sum_all(&values, k, target);
+                let mut got: Vec<Vec<usize>> = all.into_iter().map(|m| m.indices).collect();
+                got.sort();
+                let mut expected = expected;
+                expected.sort();
+                assert_eq!(
+                    got, expected,
+                    "{:?}, k = {}, target = {}",
+                    values, k, target
+                );
 
-        for &y in &vec[i..n] {
-            match map.get(&(x + y)) {
-                Some(&z) => return (x as u32) * (y as u32) * (z as u32),
-                None => continue,
+                let first = k_sum(&values, k, target);
+                assert_eq!(first.is_some(), !expected.is_empty());
             }
         }
     }
-    0
 }
@@ -2,22 +2,24 @@ use std::cmp::min;
 
 use aoc_runner_derive::*;
 
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
 #[aoc_generator(day10)]
-fn generate(input: &str) -> Vec<u32> {
-    let mut vec: Vec<u32> = input
-        .lines()
-        .map(|x| u32::from_str_radix(x, 10).unwrap())
-        .collect();
+pub fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+    let mut vec = error::lines(10, input)
+        .map(|line| line.parse(line.text, "an adapter rating"))
+        .collect::<Result<Vec<u32>, _>>()?;
 
     vec.sort_unstable();
     vec.insert(0, 0);
     vec.reverse();
     vec.insert(0, vec[0] + 3); // our adapter
-    vec
+    Ok(vec)
 }
 
 #[aoc(day10, part1)]
-fn solve_part1(input: &[u32]) -> u32 {
+pub fn part1(input: &[u32]) -> u32 {
     let mut differences = [0u32; 3];
     for s in input.windows(2) {
         differences[(s[0] - s[1] - 1) as usize] += 1;
@@ -27,7 +29,7 @@ fn solve_part1(input: &[u32]) -> u32 {
 }
 
 #[aoc(day10, part2)]
-fn solve_part2(input: &[u32]) -> u64 {
+pub fn part2(input: &[u32]) -> u64 {
     let input: Vec<u64> = input.iter().map(|&x| x as u64).collect();
 
     let mut memory = Vec::new();
@@ -56,3 +58,79 @@ fn arrangements(index: usize, input: &[u64], memory: &mut [Option<u64>]) -> u64
     memory[index] = Some(result);
     result
 }
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<u32>;
+    type Output1 = u32;
+    type Output2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_example() {
+        let input = parse("16\n10\n15\n5\n1\n11\n7\n19\n6\n12\n4").unwrap();
+        assert_eq!(part1(&input), 7 * 5);
+        assert_eq!(part2(&input), 8);
+    }
+
+    #[test]
+    fn test_large_example() {
+        let input = parse(
+            "\
+28
+33
+18
+42
+31
+14
+46
+20
+48
+47
+24
+23
+49
+45
+19
+38
+39
+11
+1
+32
+25
+35
+8
+17
+7
+9
+4
+2
+34
+10
+3",
+        )
+        .unwrap();
+
+        assert_eq!(part1(&input), 22 * 10);
+        assert_eq!(part2(&input), 19208);
+    }
+}
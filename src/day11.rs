@@ -1,87 +1,120 @@
-use std::cmp::min;
+use std::error::Error;
+use std::fmt;
 
 use aoc_runner_derive::*;
 
+use crate::error::ParseError;
+use crate::grid::{Cell, Dir, Grid, Pos};
+use crate::solution::Solution;
+
 use Tile::*;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum Tile {
+pub enum Tile {
     Floor,
     EmptySeat,
     OccupiedSeat,
 }
 
-type TileMatrix = [Vec<Tile>];
+impl Cell for Tile {
+    const EXPECTED: &'static str = "`.`, `L` or `#`";
+
+    fn from_char(c: char) -> Option<Tile> {
+        match c {
+            '.' => Some(Floor),
+            'L' => Some(EmptySeat),
+            '#' => Some(OccupiedSeat),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Floor => '.',
+            EmptySeat => 'L',
+            OccupiedSeat => '#',
+        }
+    }
+}
 
 #[aoc_generator(day11)]
-fn generate(input: &str) -> Vec<Vec<Tile>> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| match c {
-                    '.' => Floor,
-                    'L' => EmptySeat,
-                    '#' => OccupiedSeat,
-                    _ => unreachable!(),
-                })
-                .collect()
-        })
-        .collect()
+pub fn parse(input: &str) -> Result<Grid<Tile>, ParseError> {
+    Grid::parse(11, input)
 }
 
 #[aoc(day11, part1)]
-fn solve_part1(input: &TileMatrix) -> usize {
-    let stable = advance_until_stable(input, count_occupied_neighbors, 4);
+pub fn part1(input: &Grid<Tile>) -> Result<usize, Unsettled> {
+    let stable = advance_until_stable(input, count_occupied_neighbors, 4)?;
 
-    count_occupied(&stable)
+    Ok(count_occupied(&stable))
 }
 
 #[aoc(day11, part2)]
-fn solve_part2(input: &TileMatrix) -> usize {
-    let stable = advance_until_stable(input, count_occupied_visible, 5);
+pub fn part2(input: &Grid<Tile>) -> Result<usize, Unsettled> {
+    let stable = advance_until_stable(input, count_occupied_visible, 5)?;
 
-    count_occupied(&stable)
+    Ok(count_occupied(&stable))
 }
 
+/// The seats never stop changing, and flip between two layouts forever instead.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Unsettled;
+
+impl fmt::Display for Unsettled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the seats flip between two layouts forever instead of settling")
+    }
+}
+
+impl Error for Unsettled {}
+
+/// Whether the seats stop changing under the rules of both parts.
+pub fn settles(grid: &Grid<Tile>) -> bool {
+    advance_until_stable(grid, count_occupied_neighbors, 4).is_ok()
+        && advance_until_stable(grid, count_occupied_visible, 5).is_ok()
+}
+
+// Each seat's next state is a threshold of its own state and its neighbors', which the
+// neighbors affect symmetrically, so the seats either settle or end up in a cycle of two
+// layouts, which is caught by looking two steps back.
 fn advance_until_stable(
-    matrix: &TileMatrix,
-    count: impl Fn(usize, usize, &TileMatrix) -> u8,
-    threshold: u8,
-) -> Vec<Vec<Tile>> {
-    let mut current = matrix.to_vec();
+    grid: &Grid<Tile>,
+    count: impl Fn(Pos, &Grid<Tile>) -> usize,
+    threshold: usize,
+) -> Result<Grid<Tile>, Unsettled> {
+    let mut before = None;
+    let mut current = grid.clone();
 
     loop {
-        let prev = current;
-        let out = advance_matrix(&prev, &count, threshold);
-        current = out.0;
-
-        if !out.1 {
-            break;
+        let (next, changed) = advance_grid(&current, &count, threshold);
+        if !changed {
+            return Ok(next);
+        }
+        if before.as_ref() == Some(&next) {
+            return Err(Unsettled);
         }
+        before = Some(current);
+        current = next;
     }
-    current
 }
 
-fn advance_matrix(
-    matrix: &TileMatrix,
-    count: impl Fn(usize, usize, &TileMatrix) -> u8,
-    threshold: u8,
-) -> (Vec<Vec<Tile>>, bool) {
-    let mut output = matrix.to_vec();
+fn advance_grid(
+    grid: &Grid<Tile>,
+    count: impl Fn(Pos, &Grid<Tile>) -> usize,
+    threshold: usize,
+) -> (Grid<Tile>, bool) {
+    let mut output = grid.clone();
     let mut changed = false;
 
-    for i in 0..matrix.len() {
-        for j in 0..matrix[i].len() {
-            if matrix[i][j] != Floor {
-                let occupied = count(i, j, matrix);
-                if matrix[i][j] == EmptySeat && occupied == 0 {
-                    output[i][j] = OccupiedSeat;
-                    changed = true;
-                } else if matrix[i][j] == OccupiedSeat && occupied >= threshold {
-                    output[i][j] = EmptySeat;
-                    changed = true;
-                }
+    for pos in grid.positions() {
+        if grid[pos] != Floor {
+            let occupied = count(pos, grid);
+            if grid[pos] == EmptySeat && occupied == 0 {
+                output[pos] = OccupiedSeat;
+                changed = true;
+            } else if grid[pos] == OccupiedSeat && occupied >= threshold {
+                output[pos] = EmptySeat;
+                changed = true;
             }
         }
     }
@@ -89,77 +122,79 @@ fn advance_matrix(
     (output, changed)
 }
 
-fn count_occupied_neighbors(i: usize, j: usize, matrix: &TileMatrix) -> u8 {
-    let mut count = 0;
-
-    for new_i in i.saturating_sub(1)..=min(i + 1, matrix.len() - 1) {
-        for new_j in j.saturating_sub(1)..=min(j + 1, matrix[0].len() - 1) {
-            if new_i == i && new_j == j {
-                continue;
-            }
+fn count_occupied_neighbors(pos: Pos, grid: &Grid<Tile>) -> usize {
+    grid.neighbors8(pos)
+        .filter(|&pos| grid[pos] == OccupiedSeat)
+        .count()
+}
 
-            if matrix[new_i][new_j] == OccupiedSeat {
-                count += 1;
-            }
-        }
-    }
+fn count_occupied_visible(pos: Pos, grid: &Grid<Tile>) -> usize {
+    Dir::ALL
+        .iter()
+        .filter(|&&dir| {
+            grid.ray(pos, dir)
+                .map(|pos| grid[pos])
+                .find(|&tile| tile != Floor)
+                == Some(OccupiedSeat)
+        })
+        .count()
+}
 
-    count
+fn count_occupied(grid: &Grid<Tile>) -> usize {
+    grid.iter().filter(|&&tile| tile == OccupiedSeat).count()
 }
 
-fn count_occupied_visible(i: usize, j: usize, matrix: &TileMatrix) -> u8 {
-    let mut count = 0;
-    let leni = matrix.len() as isize;
-    let lenj = matrix[0].len() as isize;
+pub struct Day11;
 
-    for offset_i in -1..=1 {
-        for offset_j in -1..=1 {
-            if offset_i == 0 && offset_j == 0 {
-                continue;
-            }
+impl Solution for Day11 {
+    const DAY: u8 = 11;
 
-            let mut new_i = i as isize + offset_i;
-            let mut new_j = j as isize + offset_j;
-            while new_i >= 0 && new_i < leni && new_j >= 0 && new_j < lenj {
-                match matrix[new_i as usize][new_j as usize] {
-                    EmptySeat => break,
-                    OccupiedSeat => {
-                        count += 1;
-                        break;
-                    }
-                    Floor => {
-                        new_i += offset_i;
-                        new_j += offset_j
-                    }
-                }
-            }
-        }
+    type Input = Grid<Tile>;
+    type Output1 = Result<usize, Unsettled>;
+    type Output2 = Result<usize, Unsettled>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
     }
 
-    count
-}
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
 
-fn count_occupied(matrix: &TileMatrix) -> usize {
-    matrix
-        .iter()
-        .flatten()
-        .filter(|x| **x == OccupiedSeat)
-        .count()
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
 }
 
-#[allow(dead_code)]
-fn print_matrix(matrix: &[Vec<Tile>]) {
-    let mut out = String::new();
-    for row in matrix {
-        for &item in row {
-            out.push(match item {
-                Floor => '.',
-                EmptySeat => 'L',
-                OccupiedSeat => '#',
-            });
-        }
-        out.push('\n');
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), Ok(37));
+        assert_eq!(part2(&input), Ok(26));
+        assert!(settles(&input));
     }
 
-    println!("{}", out);
+    #[test]
+    fn test_cycle() {
+        // under the part 1 rules this ends up flipping between two layouts
+        let input = parse(".LLL.\nLLLLL\nLLLLL\n.LLL.\n.LLLL\nLLLLL").unwrap();
+        assert!(!settles(&input));
+        assert_eq!(part1(&input), Err(Unsettled));
+    }
 }
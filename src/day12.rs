@@ -1,5 +1,8 @@
 use aoc_runner_derive::*;
 
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
 use Direction::*;
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum Direction {
@@ -48,17 +51,13 @@ impl Direction {
         let mut curr = *self;
 
         for _ in 0..times {
-            let out = curr.turn(dir);
-            curr = match out {
-                Some(val) => val,
-                None => return None,
-            }
+            curr = curr.turn(dir)?;
         }
 
         Some(curr)
     }
 
-    fn to_coords(&self) -> Option<(i32, i32)> {
+    fn to_coords(self) -> Option<(i32, i32)> {
         Some(match self {
             North => (0, 1),
             East => (1, 0),
@@ -70,23 +69,29 @@ impl Direction {
     }
 }
 
-struct Instruction(Direction, i32);
+pub struct Instruction(Direction, i32);
 
 #[aoc_generator(day12)]
-fn generate(input: &str) -> Vec<Instruction> {
-    input
-        .lines()
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    error::lines(12, input)
         .map(|line| {
-            let c = line.chars().next().unwrap();
-            let num: i32 = line[1..].parse().unwrap();
-
-            Instruction(Direction::from_char(c).unwrap(), num)
+            let c = match line.text.chars().next() {
+                Some(c) => c,
+                None => return Err(line.end("an instruction")),
+            };
+            let dir = match Direction::from_char(c) {
+                Some(dir) => dir,
+                None => return Err(line.char_error(0, "`N|E|S|W|F|L|R`")),
+            };
+            let num: i32 = line.parse(&line.text[c.len_utf8()..], "a number")?;
+
+            Ok(Instruction(dir, num))
         })
         .collect()
 }
 
 #[aoc(day12, part1)]
-fn solve_part1(input: &[Instruction]) -> i32 {
+pub fn part1(input: &[Instruction]) -> i32 {
     let mut pos: (i32, i32) = (0, 0);
 
     let mut facing = East;
@@ -110,7 +115,7 @@ fn solve_part1(input: &[Instruction]) -> i32 {
 }
 
 #[aoc(day12, part2)]
-fn solve_part2(input: &[Instruction]) -> i32 {
+pub fn part2(input: &[Instruction]) -> i32 {
     let mut pos: (i32, i32) = (0, 0);
     let mut waypoint: (i32, i32) = (10, 1);
 
@@ -130,7 +135,6 @@ fn solve_part2(input: &[Instruction]) -> i32 {
         }
     }
 
-    println!("({}, {})", pos.0, pos.1);
     pos.0.abs() + pos.1.abs()
 }
 
@@ -153,3 +157,37 @@ fn turn_waypoint(waypoint: &mut (i32, i32), dir: Direction, deg: i32) {
         }
     }
 }
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = Vec<Instruction>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = parse("F10\nN3\nF7\nR90\nF11").unwrap();
+        assert_eq!(part1(&input), 25);
+        assert_eq!(part2(&input), 286);
+    }
+}
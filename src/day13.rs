@@ -2,24 +2,43 @@ use std::collections::HashMap;
 
 use aoc_runner_derive::*;
 
-#[aoc_generator(day13, part1)]
-fn generate_part1(input: &str) -> (i32, Vec<Option<i32>>) {
-    let mut lines = input.lines();
-    let depart = lines.next().unwrap().parse().unwrap();
-    let busses = lines
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|s| s.parse().ok())
-        .collect();
+use crate::error::{self, Line, ParseError};
+use crate::solution::Solution;
+
+pub struct Notes {
+    pub depart: i64,
+    pub busses: Vec<Option<i64>>,
+}
+
+#[aoc_generator(day13)]
+pub fn parse(input: &str) -> Result<Notes, ParseError> {
+    let mut lines = error::lines(13, input);
+    let depart = match lines.next() {
+        Some(line) => line.parse(line.text, "a departure time")?,
+        None => return Err(ParseError::end_of_input(13, input, "a departure time")),
+    };
+    let busses = match lines.next() {
+        Some(line) => parse_busses(line)?,
+        None => return Err(ParseError::end_of_input(13, input, "a list of bus IDs")),
+    };
 
-    (depart, busses)
+    Ok(Notes { depart, busses })
+}
+
+fn parse_busses(line: Line) -> Result<Vec<Option<i64>>, ParseError> {
+    line.text
+        .split(',')
+        .map(|s| match s {
+            "x" => Ok(None),
+            _ => line.parse(s, "a bus ID or `x`").map(Some),
+        })
+        .collect()
 }
 
 #[aoc(day13, part1)]
-fn solve_part1(input: &(i32, Vec<Option<i32>>)) -> i32 {
-    let depart = input.0;
-    let busses = &input.1;
+pub fn part1(input: &Notes) -> i64 {
+    let depart = input.depart;
+    let busses = &input.busses;
 
     let out = busses
         .iter()
@@ -31,16 +50,7 @@ fn solve_part1(input: &(i32, Vec<Option<i32>>)) -> i32 {
     out.0 * out.1
 }
 
-#[aoc_generator(day13, part2)]
-fn generate_part2(input: &str) -> Vec<(i64, i64)> {
-    let busses: Vec<Option<i64>> = input
-        .lines()
-        .nth(1)
-        .unwrap()
-        .split(',')
-        .map(|s| s.parse().ok())
-        .collect();
-
+fn equations(busses: &[Option<i64>]) -> Vec<(i64, i64)> {
     let mut equations = Vec::new();
 
     for (i, bus) in busses.iter().enumerate() {
@@ -57,12 +67,14 @@ fn generate_part2(input: &str) -> Vec<(i64, i64)> {
 }
 
 #[aoc(day13, part2)]
-fn solve_part2(input: &[(i64, i64)]) -> i64 {
+pub fn part2(input: &Notes) -> i64 {
+    let input = equations(&input.busses);
+
     // Each entry represents the equation   t === val (mod key)
     let prime_equations = {
         let mut map = HashMap::new();
 
-        for eq in input {
+        for eq in &input {
             for factor in prime_factors(eq.1) {
                 map.insert(factor, eq.0 % factor);
             }
@@ -138,6 +150,28 @@ fn extended_gcd(a: i64, b: i64) -> (i64, i64) {
     (old_s, bezout_t)
 }
 
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = Notes;
+    type Output1 = i64;
+    type Output2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,4 +183,28 @@ mod tests {
         assert!(!is_prime(4));
         assert!(!is_prime(12));
     }
+
+    #[test]
+    fn test_example() {
+        let input = parse("939\n7,13,x,x,59,x,31,19").unwrap();
+        assert_eq!(part1(&input), 295);
+        assert_eq!(part2(&input), 1068781);
+    }
+
+    #[test]
+    fn test_part2_examples() {
+        let examples = [
+            ("17,x,13,19", 3417),
+            ("67,7,59,61", 754018),
+            ("67,x,7,59,61", 779210),
+            ("67,7,x,59,61", 1261476),
+            ("1789,37,47,1889", 1202161486),
+        ];
+
+        for &(busses, expected) in &examples {
+            // only the bus list matters for part 2
+            let input = parse(&format!("0\n{}", busses)).unwrap();
+            assert_eq!(part2(&input), expected, "{}", busses);
+        }
+    }
 }
@@ -2,33 +2,47 @@ use std::collections::HashMap;
 
 use aoc_runner_derive::*;
 
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
 use Instruction::*;
 #[derive(Copy, Clone, Debug)]
-enum Instruction {
+pub enum Instruction {
     Mask([char; 36]),
     Mem(i64, i64),
 }
 
 #[aoc_generator(day14)]
-fn generate(input: &str) -> Vec<Instruction> {
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
     let mut out = Vec::new();
 
-    for line in input.lines() {
-        if line.starts_with("mem") {
-            let a = line.find(']').unwrap();
-            let index = &line[4..a];
-            let value = &line[a + 4..];
-            out.push(Mem(index.parse().unwrap(), value.parse().unwrap()));
-        } else if line.starts_with("mask") {
-            out.push(Mask(make_mask_from_slice(&line[7..7 + 36])));
+    for line in error::lines(14, input) {
+        if let Some(rest) = line.text.strip_prefix("mem[") {
+            let (index, value) = match rest.split_once("] = ") {
+                Some(val) => val,
+                None => return Err(line.error(rest, "`address] = value`")),
+            };
+            out.push(Mem(
+                line.parse(index, "an address")?,
+                line.parse(value, "a value")?,
+            ));
+        } else if let Some(mask) = line.text.strip_prefix("mask = ") {
+            if let Some(i) = mask.find(|c| !matches!(c, '0' | '1' | 'X')) {
+                return Err(line.char_error(line.text.len() - mask.len() + i, "`0`, `1` or `X`"));
+            } else if mask.len() != 36 {
+                return Err(line.error(mask, "a mask of 36 bits"));
+            }
+            out.push(Mask(make_mask_from_slice(mask)));
+        } else {
+            return Err(line.error(line.text, "`mask = ...` or `mem[...] = ...`"));
         }
     }
 
-    out
+    Ok(out)
 }
 
 #[aoc(day14, part1)]
-fn solve_part1(input: &[Instruction]) -> i64 {
+pub fn part1(input: &[Instruction]) -> i64 {
     let mut memory: HashMap<i64, i64> = HashMap::new();
     let mut mask = ['X'; 36];
 
@@ -43,7 +57,7 @@ fn execute(inst: Instruction, mem: &mut HashMap<i64, i64>, mask: &mut [char; 36]
     match inst {
         Mask(charr) => mask.clone_from(&charr),
         Mem(a, b) => {
-            mem.insert(a, apply_mask(&mask, b));
+            mem.insert(a, apply_mask(mask, b));
         }
     }
 }
@@ -74,7 +88,7 @@ fn make_mask_from_slice(slice: &str) -> [char; 36] {
 }
 
 #[aoc(day14, part2)]
-fn solve_part2(input: &[Instruction]) -> i64 {
+pub fn part2(input: &[Instruction]) -> i64 {
     let mut memory: HashMap<i64, i64> = HashMap::new();
     let mut mask = ['0'; 36];
 
@@ -89,7 +103,7 @@ fn execute_v2(inst: Instruction, mem: &mut HashMap<i64, i64>, mask: &mut [char;
     match inst {
         Mask(charr) => mask.clone_from(&charr),
         Mem(a, b) => {
-            for addr in compute_addresses(&mask, a) {
+            for addr in compute_addresses(mask, a) {
                 mem.insert(addr, b);
             }
         }
@@ -136,9 +150,31 @@ fn permute_address_int(mut addr: i64, floating: &mut Vec<u32>, output: &mut Vec<
     floating.push(index);
 }
 
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = Vec<Instruction>;
+    type Output1 = i64;
+    type Output2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{apply_mask, make_mask_from_slice};
+    use super::*;
 
     #[test]
     fn test_apply_mask() {
@@ -147,4 +183,32 @@ mod tests {
         assert_eq!(apply_mask(&mask, 11), 73);
         assert_eq!(apply_mask(&mask, 0), 64);
     }
+
+    #[test]
+    fn test_part1_example() {
+        let input = parse(
+            "\
+mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11
+mem[7] = 101
+mem[8] = 0",
+        )
+        .unwrap();
+
+        assert_eq!(part1(&input), 165);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let input = parse(
+            "\
+mask = 000000000000000000000000000000X1001X
+mem[42] = 100
+mask = 00000000000000000000000000000000X0XX
+mem[26] = 1",
+        )
+        .unwrap();
+
+        assert_eq!(part2(&input), 208);
+    }
 }
@@ -2,18 +2,31 @@ use std::collections::HashMap;
 
 use aoc_runner_derive::*;
 
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
 #[aoc_generator(day15)]
-fn generate(input: &str) -> Vec<i64> {
-    input.split(',').map(|x| x.parse().unwrap()).collect()
+pub fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+    let mut numbers = Vec::new();
+    for line in error::lines(15, input) {
+        for x in line.text.split(',') {
+            numbers.push(line.parse(x, "a starting number")?);
+        }
+    }
+
+    if numbers.is_empty() {
+        return Err(ParseError::end_of_input(15, input, "a starting number"));
+    }
+    Ok(numbers)
 }
 
 #[aoc(day15, part1)]
-fn solve_part1(input: &[i64]) -> i64 {
+pub fn part1(input: &[i64]) -> i64 {
     find_nth(input, 2020)
 }
 
 #[aoc(day15, part2)]
-fn solve_part2(input: &[i64]) -> i64 {
+pub fn part2(input: &[i64]) -> i64 {
     find_nth(input, 30000000)
 }
 
@@ -35,3 +48,65 @@ fn find_nth(input: &[i64], n: usize) -> i64 {
     }
     last
 }
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    type Input = Vec<i64>;
+    type Output1 = i64;
+    type Output2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1_examples() {
+        let examples = [
+            ("0,3,6", 436),
+            ("1,3,2", 1),
+            ("2,1,3", 10),
+            ("1,2,3", 27),
+            ("2,3,1", 78),
+            ("3,2,1", 438),
+            ("3,1,2", 1836),
+        ];
+
+        for &(input, expected) in &examples {
+            assert_eq!(part1(&parse(input).unwrap()), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    #[ignore = "takes 30 million turns per example"]
+    fn test_part2_examples() {
+        let examples = [
+            ("0,3,6", 175594),
+            ("1,3,2", 2578),
+            ("2,1,3", 3544142),
+            ("1,2,3", 261214),
+            ("2,3,1", 6895259),
+            ("3,2,1", 18),
+            ("3,1,2", 362),
+        ];
+
+        for &(input, expected) in &examples {
+            assert_eq!(part2(&parse(input).unwrap()), expected, "{}", input);
+        }
+    }
+}
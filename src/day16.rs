@@ -1,6 +1,9 @@
 use aoc_runner_derive::*;
 
-struct Rule {
+use crate::error::{self, Line, ParseError};
+use crate::solution::Solution;
+
+pub struct Rule {
     a1: u16,
     b1: u16,
     a2: u16,
@@ -8,15 +11,17 @@ struct Rule {
 }
 
 impl Rule {
-    fn new(ranges: &str) -> Rule {
+    fn parse(line: Line, ranges: &str) -> Result<Rule, ParseError> {
         let mut split_or = ranges.split(" or ");
-        let first_range = split_or.next().unwrap().trim();
-        let second_range = split_or.next().unwrap().trim();
+        let first_range = line.take(split_or.next(), "a range")?.trim();
+        let second_range = line
+            .take(split_or.next(), "` or ` and a second range")?
+            .trim();
 
-        let (a1, b1) = parse_range(first_range);
-        let (a2, b2) = parse_range(second_range);
+        let (a1, b1) = parse_range(line, first_range)?;
+        let (a2, b2) = parse_range(line, second_range)?;
 
-        Rule { a1, b1, a2, b2 }
+        Ok(Rule { a1, b1, a2, b2 })
     }
 
     fn applies(&self, val: u16) -> bool {
@@ -24,8 +29,8 @@ impl Rule {
     }
 }
 
-type Ticket = Vec<u16>;
-type Input = (Vec<Rule>, Ticket, Vec<Ticket>);
+pub type Ticket = Vec<u16>;
+pub type Input = (Vec<Rule>, Ticket, Vec<Ticket>);
 
 #[derive(Clone)]
 struct IndexSet {
@@ -90,46 +95,74 @@ impl std::fmt::Debug for IndexSet {
 }
 
 #[aoc_generator(day16)]
-fn generate(input: &str) -> (Vec<Rule>, Ticket, Vec<Ticket>) {
+pub fn parse(input: &str) -> Result<Input, ParseError> {
     let mut rules = vec![];
-    let mut lines = input.lines();
+    let mut lines = error::lines(16, input);
 
     for line in lines.by_ref() {
-        if line.is_empty() {
+        if line.text.is_empty() {
             break;
         }
-        let mut split_colon = line.split(':');
-        let rule = Rule::new(split_colon.nth(1).unwrap());
-        rules.push(rule);
+        let ranges = match line.text.split_once(':') {
+            Some((_, ranges)) => ranges,
+            None => return Err(line.end("`:` after the field name")),
+        };
+        rules.push(Rule::parse(line, ranges)?);
     }
 
-    let my_ticket = parse_ticket(lines.nth(1).unwrap());
+    expect_header(input, lines.next(), "your ticket:")?;
+    let my_ticket = match lines.next() {
+        Some(line) => parse_ticket(line)?,
+        None => return Err(ParseError::end_of_input(16, input, "your ticket")),
+    };
 
-    lines.nth(1).unwrap();
+    expect_header(input, lines.next(), "")?;
+    expect_header(input, lines.next(), "nearby tickets:")?;
     let mut tickets = vec![];
-    tickets.extend(lines.map(parse_ticket));
+    for line in lines {
+        tickets.push(parse_ticket(line)?);
+    }
 
-    (rules, my_ticket, tickets)
+    Ok((rules, my_ticket, tickets))
 }
 
-fn parse_range(range: &str) -> (u16, u16) {
-    let mut split = range.split('-');
-    let first = split.next().unwrap().parse().unwrap();
-    let second = split.next().unwrap().parse().unwrap();
+fn expect_header(input: &str, line: Option<Line>, header: &str) -> Result<(), ParseError> {
+    match line {
+        Some(line) if line.text == header => Ok(()),
+        Some(line) => Err(line.error(line.text, describe(header))),
+        None => Err(ParseError::end_of_input(16, input, describe(header))),
+    }
+}
 
-    (first, second)
+fn describe(header: &str) -> String {
+    if header.is_empty() {
+        "a blank line".to_string()
+    } else {
+        format!("`{}`", header)
+    }
 }
 
-fn parse_ticket(ticket: &str) -> Ticket {
-    ticket
+fn parse_range(line: Line, range: &str) -> Result<(u16, u16), ParseError> {
+    let (first, second) = match range.split_once('-') {
+        Some(val) => val,
+        None => return Err(line.error(range, "a `min-max` range")),
+    };
+
+    Ok((
+        line.parse(first, "a number")?,
+        line.parse(second, "a number")?,
+    ))
+}
+
+fn parse_ticket(line: Line) -> Result<Ticket, ParseError> {
+    line.text
         .split(',')
-        .map(str::parse)
-        .map(Result::unwrap)
+        .map(|x| line.parse(x, "a ticket value"))
         .collect()
 }
 
 #[aoc(day16, part1)]
-fn solve_part1(input: &Input) -> u16 {
+pub fn part1(input: &Input) -> u32 {
     let tickets = &input.2;
 
     tickets
@@ -138,8 +171,12 @@ fn solve_part1(input: &Input) -> u16 {
         .sum()
 }
 
-fn invalid_ticket_values(ticket: &[u16], rules: &[Rule]) -> u16 {
-    ticket.iter().filter(|x| !is_valid_value(**x, rules)).sum()
+fn invalid_ticket_values(ticket: &[u16], rules: &[Rule]) -> u32 {
+    ticket
+        .iter()
+        .filter(|x| !is_valid_value(**x, rules))
+        .map(|&x| x as u32)
+        .sum()
 }
 
 fn is_valid_value(val: u16, rules: &[Rule]) -> bool {
@@ -147,12 +184,24 @@ fn is_valid_value(val: u16, rules: &[Rule]) -> bool {
 }
 
 #[aoc(day16, part2)]
-fn solve_part2(input: &Input) -> u64 {
+pub fn part2(input: &Input) -> u64 {
+    let rule_positions = field_positions(input);
+    let my_ticket = &input.1;
+
+    rule_positions
+        .iter()
+        .take(6)
+        .map(|rule_pos| my_ticket[*rule_pos] as u64)
+        .product()
+}
+
+// Finds the position on the ticket of the field described by each rule.
+fn field_positions(input: &Input) -> Vec<usize> {
     let valid_tickets: Vec<Ticket> = input
         .2
         .iter()
-        .cloned()
         .filter(|ticket| is_valid_ticket(ticket, &input.0))
+        .cloned()
         .collect();
 
     let rules = &input.0;
@@ -171,14 +220,7 @@ fn solve_part2(input: &Input) -> u64 {
     // at this point, rule_positions contains a list of possible positions
     // for each rule.
 
-    let rule_positions = find_rules_permutation(rules, &rule_positions);
-    let my_ticket = &input.1;
-
-    rule_positions
-        .iter()
-        .take(6)
-        .map(|rule_pos| my_ticket[*rule_pos] as u64)
-        .product()
+    find_rules_permutation(rules, &rule_positions)
 }
 
 fn is_valid_ticket(ticket: &[u16], rules: &[Rule]) -> bool {
@@ -226,3 +268,85 @@ fn find_rules_permutation_inner(
 
     false
 }
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+
+    type Input = Input;
+    type Output1 = u32;
+    type Output2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1_example() {
+        let input = parse(
+            "\
+class: 1-3 or 5-7
+row: 6-11 or 33-44
+seat: 13-40 or 45-50
+
+your ticket:
+7,1,14
+
+nearby tickets:
+7,3,47
+40,4,50
+55,2,20
+38,6,12",
+        )
+        .unwrap();
+
+        assert_eq!(part1(&input), 71);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let input = parse(
+            "\
+class: 0-1 or 4-19
+row: 0-5 or 8-19
+seat: 0-13 or 16-19
+
+your ticket:
+11,12,13
+
+nearby tickets:
+3,9,18
+15,1,5
+5,14,9",
+        )
+        .unwrap();
+
+        // class is the second field, row the first and seat the third
+        assert_eq!(field_positions(&input), vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn test_separator_must_be_blank() {
+        let err = match parse("class: 1-3 or 5-7\n\nyour ticket:\n7\nnearby\nnearby tickets:\n7") {
+            Ok(_) => panic!("a non-blank separator was accepted"),
+            Err(err) => err,
+        };
+
+        assert!(err.to_string().starts_with("day 16, line 5"), "{}", err);
+        assert!(err.to_string().contains("a blank line"), "{}", err);
+    }
+}
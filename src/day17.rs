@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+
+use aoc_runner_derive::*;
+
+use crate::error::ParseError;
+use crate::grid::{Cell, Grid, Pos};
+use crate::solution::Solution;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Cube {
+    Inactive,
+    Active,
+}
+
+impl Cell for Cube {
+    const EXPECTED: &'static str = "`.` or `#`";
+
+    fn from_char(c: char) -> Option<Cube> {
+        match c {
+            '.' => Some(Cube::Inactive),
+            '#' => Some(Cube::Active),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Cube::Inactive => '.',
+            Cube::Active => '#',
+        }
+    }
+}
+
+/// An infinite `N`-dimensional grid of cubes, of which only the active ones are stored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pocket<const N: usize> {
+    active: HashSet<[i64; N]>,
+}
+
+impl<const N: usize> Pocket<N> {
+    /// Places a two-dimensional slice at the origin, with every other coordinate zero.
+    pub fn from_slice(slice: &Grid<Cube>) -> Pocket<N> {
+        assert!(N >= 2, "a pocket needs at least two dimensions");
+
+        let active = slice
+            .positions()
+            .filter(|&pos| slice[pos] == Cube::Active)
+            .map(|Pos { row, col }| {
+                let mut coords = [0; N];
+                coords[0] = col as i64;
+                coords[1] = row as i64;
+                coords
+            })
+            .collect();
+
+        Pocket { active }
+    }
+
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    pub fn is_active(&self, coords: &[i64; N]) -> bool {
+        self.active.contains(coords)
+    }
+
+    /// Runs one cycle: an active cube stays active with 2 or 3 active neighbors,
+    /// an inactive one becomes active with exactly 3.
+    pub fn step(&self) -> Pocket<N> {
+        let offsets = neighbor_offsets::<N>();
+        // only counts of 2 and 3 matter, and in five or more dimensions a count can pass 255
+        let mut neighbors: HashMap<[i64; N], u8> = HashMap::new();
+
+        for cube in &self.active {
+            for offset in &offsets {
+                let mut neighbor = *cube;
+                for (x, dx) in neighbor.iter_mut().zip(offset) {
+                    *x += dx;
+                }
+                let count = neighbors.entry(neighbor).or_insert(0);
+                *count = count.saturating_add(1);
+            }
+        }
+
+        let active = neighbors
+            .into_iter()
+            .filter(|(cube, count)| *count == 3 || (*count == 2 && self.is_active(cube)))
+            .map(|(cube, _)| cube)
+            .collect();
+
+        Pocket { active }
+    }
+
+    pub fn run(&self, cycles: usize) -> Pocket<N> {
+        (0..cycles).fold(self.clone(), |pocket, _| pocket.step())
+    }
+}
+
+// Every offset with each coordinate in -1..=1, except for all zeroes.
+fn neighbor_offsets<const N: usize>() -> Vec<[i64; N]> {
+    let count = 3usize.pow(N as u32);
+
+    (0..count)
+        .map(|mut i| {
+            let mut offset = [0; N];
+            for x in &mut offset {
+                *x = (i % 3) as i64 - 1;
+                i /= 3;
+            }
+            offset
+        })
+        .filter(|offset| offset.iter().any(|&x| x != 0))
+        .collect()
+}
+
+/// How many cubes are active after `cycles` cycles, starting from `slice` in `N` dimensions.
+pub fn simulate<const N: usize>(slice: &Grid<Cube>, cycles: usize) -> usize {
+    Pocket::<N>::from_slice(slice).run(cycles).len()
+}
+
+#[aoc_generator(day17)]
+pub fn parse(input: &str) -> Result<Grid<Cube>, ParseError> {
+    Grid::parse(17, input)
+}
+
+#[aoc(day17, part1)]
+pub fn part1(input: &Grid<Cube>) -> usize {
+    simulate::<3>(input, 6)
+}
+
+#[aoc(day17, part2)]
+pub fn part2(input: &Grid<Cube>) -> usize {
+    simulate::<4>(input, 6)
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u8 = 17;
+
+    type Input = Grid<Cube>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+.#.
+..#
+###";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), 112);
+        assert_eq!(part2(&input), 848);
+    }
+
+    #[test]
+    fn test_first_cycle() {
+        let input = parse(EXAMPLE).unwrap();
+        let pocket = Pocket::<3>::from_slice(&input).step();
+
+        assert_eq!(pocket.len(), 11);
+        assert!(pocket.is_active(&[0, 1, -1]));
+        assert!(!pocket.is_active(&[1, 0, 0]));
+    }
+
+    #[test]
+    fn test_other_dimensions() {
+        // in two dimensions this is plain Game of Life, where a blinker has period 2
+        let blinker = parse("...\n###\n...").unwrap();
+        let pocket = Pocket::<2>::from_slice(&blinker);
+        assert_ne!(pocket.step(), pocket);
+        assert_eq!(pocket.run(2), pocket);
+
+        assert_eq!(neighbor_offsets::<5>().len(), 242);
+    }
+
+    #[test]
+    fn test_dense_block() {
+        // every cube of a 3x3x3x3x3x3 block, so the middle one has 728 active neighbors
+        let mut offsets = neighbor_offsets::<6>();
+        offsets.push([0; 6]);
+        let pocket = Pocket::<6> {
+            active: offsets.into_iter().collect(),
+        };
+
+        let next = pocket.step();
+        assert!(!next.is_active(&[0; 6]));
+        assert!(!next.is_active(&[1; 6]));
+        // just outside the block, touching exactly three of it
+        assert!(next.is_active(&[2, 2, 2, 2, 2, 0]));
+    }
+}
@@ -0,0 +1,444 @@
+use std::error::Error;
+use std::fmt;
+use std::iter::Peekable;
+use std::slice;
+
+use aoc_runner_derive::*;
+
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Mul,
+}
+
+impl Op {
+    /// `None` if the result doesn't fit in 64 bits.
+    pub fn apply(self, a: u64, b: u64) -> Option<u64> {
+        match self {
+            Op::Add => a.checked_add(b),
+            Op::Mul => a.checked_mul(b),
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Op::Add => "+",
+            Op::Mul => "*",
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    Num(u64),
+    Op(Op),
+    Open,
+    Close,
+}
+
+/// Splits a line into tokens, along with the byte index each one starts at.
+pub fn tokenize(line: &error::Line) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = line.text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let token = match c {
+            ' ' => continue,
+            '+' => Token::Op(Op::Add),
+            '*' => Token::Op(Op::Mul),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '0'..='9' => {
+                let mut end = i + 1;
+                while let Some(&(j, '0'..='9')) = chars.peek() {
+                    end = j + 1;
+                    chars.next();
+                }
+                Token::Num(line.parse(&line.text[i..end], "a number that fits in 64 bits")?)
+            }
+            _ => return Err(line.char_error(i, "a number, an operator or a parenthesis")),
+        };
+        tokens.push((i, token));
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+/// How tightly an operator binds: higher precedences are applied first, and
+/// associativity decides between two operators of the same precedence.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Binding {
+    pub precedence: u8,
+    pub assoc: Assoc,
+}
+
+impl Binding {
+    pub const fn left(precedence: u8) -> Binding {
+        Binding {
+            precedence,
+            assoc: Assoc::Left,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Table {
+    pub add: Binding,
+    pub mul: Binding,
+}
+
+impl Table {
+    /// Everything is evaluated left to right, as in part 1.
+    pub const EQUAL: Table = Table {
+        add: Binding::left(1),
+        mul: Binding::left(1),
+    };
+    /// Addition comes before multiplication, as in part 2.
+    pub const ADD_FIRST: Table = Table {
+        add: Binding::left(2),
+        mul: Binding::left(1),
+    };
+    /// The precedence taught in school.
+    pub const MUL_FIRST: Table = Table {
+        add: Binding::left(1),
+        mul: Binding::left(2),
+    };
+
+    pub fn binding(&self, op: Op) -> Binding {
+        match op {
+            Op::Add => self.add,
+            Op::Mul => self.mul,
+        }
+    }
+}
+
+/// An expression as it was written: operands joined by operators, before any precedence is
+/// applied. A [`Table`] decides how the operators group when it is turned into a [`Tree`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expr {
+    pub first: Operand,
+    pub rest: Vec<(Op, Operand)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Operand {
+    Num(u64),
+    Paren(Box<Expr>),
+}
+
+impl Expr {
+    /// Groups the operators by precedence climbing, with them binding as in `table`.
+    pub fn tree(&self, table: &Table) -> Tree {
+        climb(
+            self.first.tree(table),
+            &mut self.rest.iter().peekable(),
+            table,
+            0,
+        )
+    }
+
+    /// `None` if any step along the way doesn't fit in 64 bits.
+    pub fn eval(&self, table: &Table) -> Option<u64> {
+        self.tree(table).eval()
+    }
+}
+
+impl Operand {
+    fn tree(&self, table: &Table) -> Tree {
+        match self {
+            Operand::Num(n) => Tree::Num(*n),
+            Operand::Paren(expr) => expr.tree(table),
+        }
+    }
+}
+
+// Joins `lhs` with the operators and operands after it that bind at least as tightly as
+// `min_precedence`.
+fn climb(
+    mut lhs: Tree,
+    rest: &mut Peekable<slice::Iter<(Op, Operand)>>,
+    table: &Table,
+    min_precedence: u16,
+) -> Tree {
+    while let Some((op, operand)) = rest.peek().copied() {
+        let binding = table.binding(*op);
+        if u16::from(binding.precedence) < min_precedence {
+            break;
+        }
+        rest.next();
+
+        let next_min = match binding.assoc {
+            Assoc::Left => u16::from(binding.precedence) + 1,
+            Assoc::Right => u16::from(binding.precedence),
+        };
+        let rhs = climb(operand.tree(table), rest, table, next_min);
+        lhs = Tree::BinOp(*op, Box::new(lhs), Box::new(rhs));
+    }
+
+    lhs
+}
+
+/// An expression with every operator applied to exactly two operands, in the order a table
+/// says.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Tree {
+    Num(u64),
+    BinOp(Op, Box<Tree>, Box<Tree>),
+}
+
+impl Tree {
+    /// `None` if any step along the way doesn't fit in 64 bits.
+    pub fn eval(&self) -> Option<u64> {
+        match self {
+            Tree::Num(n) => Some(*n),
+            Tree::BinOp(op, a, b) => op.apply(a.eval()?, b.eval()?),
+        }
+    }
+}
+
+/// Shows every operation in parentheses, to make the shape of the tree visible.
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Tree::Num(n) => write!(f, "{}", n),
+            Tree::BinOp(op, a, b) => write!(f, "({} {} {})", a, op, b),
+        }
+    }
+}
+
+/// Where a list of tokens stopped making sense: the index of the offending token,
+/// or the number of tokens if they ended too early.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub token: usize,
+    pub expected: &'static str,
+}
+
+/// Parses tokens into an expression, which doesn't depend on any precedences yet.
+pub fn parse_expr(tokens: &[Token]) -> Result<Expr, SyntaxError> {
+    let mut parser = Parser { tokens, pos: 0 };
+
+    let expr = parser.expr()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(_) => Err(parser.error("an operator")),
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    fn error(&self, expected: &'static str) -> SyntaxError {
+        SyntaxError {
+            token: self.pos.min(self.tokens.len()),
+            expected,
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, SyntaxError> {
+        let first = self.operand()?;
+        let mut rest = Vec::new();
+
+        while let Some(Token::Op(op)) = self.peek() {
+            self.next();
+            rest.push((op, self.operand()?));
+        }
+
+        Ok(Expr { first, rest })
+    }
+
+    fn operand(&mut self) -> Result<Operand, SyntaxError> {
+        match self.peek() {
+            Some(Token::Num(n)) => {
+                self.next();
+                Ok(Operand::Num(n))
+            }
+            Some(Token::Open) => {
+                self.next();
+                let expr = self.expr()?;
+                match self.peek() {
+                    Some(Token::Close) => {
+                        self.next();
+                        Ok(Operand::Paren(Box::new(expr)))
+                    }
+                    _ => Err(self.error("an operator or `)`")),
+                }
+            }
+            _ => Err(self.error("a number or `(`")),
+        }
+    }
+}
+
+/// The homework's total doesn't fit in 64 bits, or one of its lines doesn't on the way there.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the homework doesn't add up within 64 bits")
+    }
+}
+
+impl Error for Overflow {}
+
+/// The sum of every line of homework, evaluated with the given precedences.
+pub fn evaluate_all(input: &[Expr], table: &Table) -> Result<u64, Overflow> {
+    input.iter().try_fold(0u64, |sum, expr| {
+        expr.eval(table)
+            .and_then(|value| sum.checked_add(value))
+            .ok_or(Overflow)
+    })
+}
+
+#[aoc_generator(day18)]
+pub fn parse(input: &str) -> Result<Vec<Expr>, ParseError> {
+    error::lines(18, input)
+        .map(|line| {
+            let (indices, tokens): (Vec<usize>, Vec<Token>) = tokenize(&line)?.into_iter().unzip();
+
+            parse_expr(&tokens).map_err(|err| match indices.get(err.token) {
+                Some(&i) => line.char_error(i, err.expected),
+                None => line.end(err.expected),
+            })
+        })
+        .collect()
+}
+
+#[aoc(day18, part1)]
+pub fn part1(input: &[Expr]) -> Result<u64, Overflow> {
+    evaluate_all(input, &Table::EQUAL)
+}
+
+#[aoc(day18, part2)]
+pub fn part2(input: &[Expr]) -> Result<u64, Overflow> {
+    evaluate_all(input, &Table::ADD_FIRST)
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+
+    type Input = Vec<Expr>;
+    type Output1 = Result<u64, Overflow>;
+    type Output2 = Result<u64, Overflow>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLES: [(&str, u64, u64); 6] = [
+        ("1 + 2 * 3 + 4 * 5 + 6", 71, 231),
+        ("1 + (2 * 3) + (4 * (5 + 6))", 51, 51),
+        ("2 * 3 + (4 * 5)", 26, 46),
+        ("5 + (8 * 3 + 9 + 3 * 4 * 3)", 437, 1445),
+        ("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", 12240, 669060),
+        (
+            "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+            13632,
+            23340,
+        ),
+    ];
+
+    #[test]
+    fn test_examples() {
+        for &(example, expected1, expected2) in &EXAMPLES {
+            let input = parse(example).unwrap();
+            assert_eq!(part1(&input), Ok(expected1), "{}", example);
+            assert_eq!(part2(&input), Ok(expected2), "{}", example);
+        }
+    }
+
+    #[test]
+    fn test_tables() {
+        let expr = &parse("1 + 2 * 3 + 4").unwrap()[0];
+        let tree = |table| expr.tree(&table).to_string();
+
+        assert_eq!(tree(Table::EQUAL), "(((1 + 2) * 3) + 4)");
+        assert_eq!(tree(Table::ADD_FIRST), "((1 + 2) * (3 + 4))");
+        assert_eq!(tree(Table::MUL_FIRST), "((1 + (2 * 3)) + 4)");
+
+        let right = Table {
+            add: Binding {
+                precedence: 1,
+                assoc: Assoc::Right,
+            },
+            mul: Binding::left(1),
+        };
+        assert_eq!(tree(right), "(1 + ((2 * 3) + 4))");
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let err = parse("1 + 2\n1 + * 2").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 5));
+        assert_eq!(err.expected, "a number or `(`");
+
+        let err = parse("(1 + 2").unwrap_err();
+        assert_eq!(
+            (err.column, err.expected.as_str()),
+            (7, "an operator or `)`")
+        );
+
+        let err = parse("1 + 2)").unwrap_err();
+        assert_eq!((err.column, err.got.as_str()), (6, ")"));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let big = "18446744073709551615";
+        assert_eq!(part1(&parse(big).unwrap()), Ok(u64::MAX));
+        assert_eq!(
+            part1(&parse(&format!("{} + 1", big)).unwrap()),
+            Err(Overflow)
+        );
+
+        // each line fits, but not both together
+        assert_eq!(
+            part1(&parse(&format!("{}\n1", big)).unwrap()),
+            Err(Overflow)
+        );
+
+        // only part 2 multiplies these
+        let input = parse("4294967296 * 0 + 4294967296").unwrap();
+        assert_eq!(part1(&input), Ok(4294967296));
+        assert_eq!(part2(&input), Err(Overflow));
+    }
+}
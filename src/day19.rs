@@ -0,0 +1,245 @@
+use std::collections::HashSet;
+
+use aoc_runner_derive::*;
+
+use crate::error::{self, Line, ParseError};
+use crate::grammar::{Derivation, Grammar, Rule, RuleId};
+use crate::solution::Solution;
+
+#[derive(Clone, Debug)]
+pub struct Input {
+    pub grammar: Grammar,
+    pub messages: Vec<String>,
+}
+
+static LOOPING_RULES: &str = "\
+8: 42 | 42 8
+11: 42 31 | 42 11 31";
+
+/// A rule's id, the rule, and the rules it refers to along with the tokens naming them.
+pub type ParsedRule<'a> = (RuleId, Rule, Vec<(RuleId, &'a str)>);
+
+/// Parses a rule like `1: 2 3 | 3 2` or `4: "a"`, returning it along with the rules it refers
+/// to and the tokens naming them, so that errors about them can point at them.
+pub fn parse_rule<'a>(line: &Line<'a>) -> Result<ParsedRule<'a>, ParseError> {
+    let text = line.text;
+    let colon = text.find(':').ok_or_else(|| line.end("`:`"))?;
+    let id = line.parse(&text[..colon], "a rule number")?;
+    let body = text[colon + 1..].trim_start();
+
+    if body.starts_with('"') {
+        return match body.as_bytes() {
+            [b'"', c, b'"'] if c.is_ascii() => Ok((id, Rule::Char(*c), Vec::new())),
+            _ => Err(line.error(body, "a single character in quotes")),
+        };
+    }
+
+    let mut references = Vec::new();
+    let mut alts = Vec::new();
+    for alt in body.split('|') {
+        let mut seq = Vec::new();
+        for token in alt.split_whitespace() {
+            let id = line.parse(token, "a rule number")?;
+            seq.push(id);
+            references.push((id, token));
+        }
+
+        if seq.is_empty() {
+            return Err(line.error(alt, "at least one rule number"));
+        }
+        alts.push(seq);
+    }
+
+    Ok((id, Rule::Alt(alts), references))
+}
+
+// Adds the rules to `grammar`, replacing any it already has, as part 2 does. Defining a rule
+// twice among `lines` is an error.
+fn parse_rules<'a>(
+    lines: impl Iterator<Item = Line<'a>>,
+    grammar: &mut Grammar,
+) -> Result<Vec<(Line<'a>, RuleId, &'a str)>, ParseError> {
+    let mut references = Vec::new();
+    let mut defined = HashSet::new();
+    for line in lines {
+        let (id, rule, refs) = parse_rule(&line)?;
+        if !defined.insert(id) {
+            let token = line.text.split(':').next().unwrap();
+            return Err(line.error(token, "a rule id not already defined"));
+        }
+        grammar.insert(id, rule);
+        references.extend(refs.into_iter().map(|(id, token)| (line, id, token)));
+    }
+
+    Ok(references)
+}
+
+#[aoc_generator(day19)]
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let mut lines = error::lines(19, input);
+    let mut grammar = Grammar::new();
+
+    let references = parse_rules(
+        lines.by_ref().take_while(|line| !line.text.is_empty()),
+        &mut grammar,
+    )?;
+    for (line, id, token) in references {
+        if grammar.get(id).is_none() {
+            return Err(line.error(token, "a rule that is defined"));
+        }
+    }
+    if grammar.get(0).is_none() {
+        return Err(ParseError::end_of_input(19, input, "a rule 0"));
+    }
+
+    let messages = lines.map(|line| line.text.to_string()).collect();
+
+    Ok(Input { grammar, messages })
+}
+
+/// How a message matches rule 0, if it does.
+pub fn explain(grammar: &Grammar, message: &str) -> Option<Derivation> {
+    grammar.derive(0, message)
+}
+
+fn count_matching(grammar: &Grammar, messages: &[String]) -> usize {
+    messages
+        .iter()
+        .filter(|message| grammar.matches(0, message))
+        .count()
+}
+
+#[aoc(day19, part1)]
+pub fn part1(input: &Input) -> usize {
+    count_matching(&input.grammar, &input.messages)
+}
+
+#[aoc(day19, part2)]
+pub fn part2(input: &Input) -> usize {
+    let mut grammar = input.grammar.clone();
+    parse_rules(error::lines(19, LOOPING_RULES), &mut grammar)
+        .expect("the replacement rules are well-formed");
+
+    count_matching(&grammar, &input.messages)
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u8 = 19;
+
+    type Input = Input;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE1: &str = r#"0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: "a"
+5: "b"
+
+ababbb
+bababa
+abbbab
+aaabbb
+aaaabbb"#;
+
+    static EXAMPLE2: &str = r#"42: 9 14 | 10 1
+9: 14 27 | 1 26
+10: 23 14 | 28 1
+1: "a"
+11: 42 31
+5: 1 14 | 15 1
+19: 14 1 | 14 14
+12: 24 14 | 19 1
+16: 15 1 | 14 14
+31: 14 17 | 1 13
+6: 14 14 | 1 14
+2: 1 24 | 14 4
+0: 8 11
+13: 14 3 | 1 12
+15: 1 | 14
+17: 14 2 | 1 7
+23: 25 1 | 22 14
+28: 16 1
+4: 1 1
+20: 14 14 | 1 15
+3: 5 14 | 16 1
+27: 1 6 | 14 18
+14: "b"
+21: 14 1 | 1 14
+25: 1 1 | 1 14
+22: 14 14
+8: 42
+26: 14 22 | 1 20
+18: 15 15
+7: 14 5 | 1 21
+24: 14 1
+
+abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa
+bbabbbbaabaabba
+babbbbaabbbbbabbbbbbaabaaabaaa
+aaabbbbbbaaaabaababaabababbabaaabbababababaaa
+bbbbbbbaaaabbbbaaabbabaaa
+bbbababbbbaaaaaaaabbababaaababaabab
+ababaaaaaabaaab
+ababaaaaabbbaba
+baabbaaaabbaaaababbaababb
+abbbbabbbbaaaababbbbbbaaaababb
+aaaaabbaabaaaaababaa
+aaaabbaaaabbaaa
+aaaabbaabbaaaaaaabbbabbbaaabbaabaaa
+babaaabbbaaabaababbaabababaaab
+aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"#;
+
+    #[test]
+    fn test_examples() {
+        let input = parse(EXAMPLE1).unwrap();
+        assert_eq!(part1(&input), 2);
+
+        let input = parse(EXAMPLE2).unwrap();
+        assert_eq!(part1(&input), 3);
+        assert_eq!(part2(&input), 12);
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = parse(EXAMPLE1).unwrap();
+        let derivation = explain(&input.grammar, "ababbb").unwrap();
+
+        assert_eq!(
+            derivation.to_string(),
+            "0(4('a') 1(3(5('b') 4('a')) 2(5('b') 5('b'))) 5('b'))"
+        );
+        assert_eq!(explain(&input.grammar, "bababa"), None);
+    }
+
+    #[test]
+    fn test_rule_errors() {
+        let err = parse("0: 1 2\n1: \"a\"\n\na").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 6));
+        assert_eq!(err.expected, "a rule that is defined");
+
+        let err = parse("0: 1\n1: \"a\"\n1: \"b\"\n\na").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.expected, "a rule id not already defined");
+    }
+}
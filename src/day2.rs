@@ -1,5 +1,12 @@
+use std::fmt;
+
 use aoc_runner_derive::*;
 
+use crate::error::{self, Line, ParseError};
+use crate::policy::{self, LetterCount, OnePosition};
+use crate::solution::Solution;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Password {
     pub lower: u8,
     pub upper: u8,
@@ -8,68 +15,99 @@ pub struct Password {
 }
 
 impl Password {
-    pub fn parse(string: &str) -> Password {
-        let hyphen_i = string.find('-').unwrap();
-        let space_i = string.find(' ').unwrap();
-
-        Password {
-            lower: u8::from_str_radix(&string[..hyphen_i], 10).unwrap(),
-            upper: u8::from_str_radix(&string[hyphen_i + 1..space_i], 10).unwrap(),
-            letter: string[space_i + 1..].chars().next().unwrap(),
-            password: string[space_i + 4..].to_string(),
-        }
-    }
+    pub fn parse(line: Line) -> Result<Password, ParseError> {
+        let (range, rest) = match line.text.split_once(' ') {
+            Some(val) => val,
+            None => return Err(line.end("a space after the `min-max` range")),
+        };
+        let (lower, upper) = match range.split_once('-') {
+            Some(val) => val,
+            None => return Err(line.error(range, "a `min-max` range")),
+        };
+        let (letter, password) = match rest.split_once(": ") {
+            Some(val) => val,
+            None => return Err(line.error(rest, "`letter: password`")),
+        };
 
-    pub fn is_valid1(&self) -> bool {
-        let mut letter_count = 0;
-        for c in self.password.chars() {
-            if c == self.letter {
-                letter_count += 1;
-                if letter_count > self.upper {
-                    return false;
-                }
-            }
-        }
-        letter_count >= self.lower
+        let mut letter_chars = letter.chars();
+        let letter = match (letter_chars.next(), letter_chars.next()) {
+            (Some(c), None) => c,
+            _ => return Err(line.error(letter, "a single letter")),
+        };
+
+        Ok(Password {
+            lower: line.parse(lower, "a number")?,
+            upper: line.parse(upper, "a number")?,
+            letter,
+            password: password.to_string(),
+        })
     }
+}
 
-    pub fn is_valid2(&self) -> bool {
-        let mut chars = self.password.chars();
-        let a = self.lower as usize - 1;
-        let b = self.upper as usize - 1;
-        (chars.nth(a).unwrap() == self.letter) ^ (chars.nth(b - a - 1).unwrap() == self.letter)
+impl fmt::Display for Password {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{} {}: {}",
+            self.lower, self.upper, self.letter, self.password
+        )
     }
 }
 
 #[aoc_generator(day2)]
-fn generate_passwords(input: &str) -> Vec<Password> {
+pub fn parse(input: &str) -> Result<Vec<Password>, ParseError> {
     let mut vec = Vec::new();
-    for password in input.lines() {
-        vec.push(Password::parse(password));
+    for line in error::lines(2, input) {
+        vec.push(Password::parse(line)?);
     }
-    vec
+    Ok(vec)
 }
 
 #[aoc(day2, part1)]
-fn solve_part1(input: &[Password]) -> u32 {
-    let mut counter = 0;
+pub fn part1(input: &[Password]) -> usize {
+    policy::count_valid(&LetterCount, input)
+}
+
+#[aoc(day2, part2)]
+pub fn part2(input: &[Password]) -> usize {
+    policy::count_valid(&OnePosition, input)
+}
 
-    for pwd in input {
-        if pwd.is_valid1() {
-            counter += 1;
-        }
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Password>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
     }
-    counter
 }
 
-#[aoc(day2, part2)]
-fn solve_part2(input: &[Password]) -> u32 {
-    let mut counter = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc";
 
-    for pwd in input {
-        if pwd.is_valid2() {
-            counter += 1;
-        }
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), 2);
+        assert_eq!(part2(&input), 1);
     }
-    counter
 }
@@ -0,0 +1,531 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+use aoc_runner_derive::*;
+
+use crate::error::{self, ParseError};
+use crate::grid::{Cell, Grid, Orientation, Pos};
+use crate::solution::Solution;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Pixel {
+    Off,
+    On,
+}
+
+impl Cell for Pixel {
+    const EXPECTED: &'static str = "`.` or `#`";
+
+    fn from_char(c: char) -> Option<Pixel> {
+        match c {
+            '.' => Some(Pixel::Off),
+            '#' => Some(Pixel::On),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Pixel::Off => '.',
+            Pixel::On => '#',
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Tile {
+    pub id: u64,
+    pub pixels: Grid<Pixel>,
+}
+
+/// Which tile goes in a spot of the assembled image, and how it is turned.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Placement {
+    pub tile: usize,
+    pub orientation: Orientation,
+}
+
+#[derive(Clone, Debug)]
+pub struct Input {
+    pub tiles: Vec<Tile>,
+}
+
+/// How the tiles fit together, and the image they make with their borders removed.
+#[derive(Clone, Debug)]
+pub struct Assembled {
+    pub layout: Grid<Placement>,
+    pub image: Grid<Pixel>,
+}
+
+impl Input {
+    /// Fits the tiles together, which each part does for itself so that its time includes it.
+    pub fn assemble(&self) -> Result<Assembled, NoFit> {
+        let layout = assemble(&self.tiles).ok_or(NoFit)?;
+        let image = image(&self.tiles, &layout);
+        Ok(Assembled { layout, image })
+    }
+}
+
+/// The tiles can't be arranged into a square with all touching borders matching.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NoFit;
+
+impl fmt::Display for NoFit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the tiles don't fit together into a square")
+    }
+}
+
+impl Error for NoFit {}
+
+const TOP: usize = 0;
+const RIGHT: usize = 1;
+const BOTTOM: usize = 2;
+const LEFT: usize = 3;
+
+// The borders of a tile as bits, read left to right and top to bottom, so that
+// touching borders of neighboring tiles are equal.
+fn borders(pixels: &Grid<Pixel>) -> [u32; 4] {
+    let side = pixels.width();
+    let bits = |pos: &dyn Fn(usize) -> Pos| {
+        (0..side).fold(0, |acc, i| acc << 1 | (pixels[pos(i)] == Pixel::On) as u32)
+    };
+
+    [
+        bits(&|i| Pos::new(0, i)),
+        bits(&|i| Pos::new(i, side - 1)),
+        bits(&|i| Pos::new(side - 1, i)),
+        bits(&|i| Pos::new(i, 0)),
+    ]
+}
+
+// The same for a border and its mirror image, since the neighbor may be flipped.
+fn canonical(border: u32, side: usize) -> u32 {
+    let reversed = border.reverse_bits() >> (32 - side);
+    border.min(reversed)
+}
+
+/// Arranges the tiles into a square so that all touching borders match, or returns `None` if
+/// they cannot be. The top left corner is placed first, and the rest are fitted one by one.
+pub fn assemble(tiles: &[Tile]) -> Option<Grid<Placement>> {
+    let per_side = (tiles.len() as f64).sqrt().round() as usize;
+    if per_side * per_side != tiles.len() || tiles.is_empty() {
+        return None;
+    }
+
+    let side = tiles[0].pixels.width();
+    let oriented: Vec<Vec<(Orientation, [u32; 4])>> = tiles
+        .iter()
+        .map(|tile| {
+            Orientation::ALL
+                .iter()
+                .map(|&o| (o, borders(&tile.pixels.oriented(o))))
+                .collect()
+        })
+        .collect();
+
+    let mut counts: HashMap<u32, usize> = HashMap::new();
+    for tile in &oriented {
+        for &border in &tile[0].1 {
+            *counts.entry(canonical(border, side)).or_insert(0) += 1;
+        }
+    }
+    let unmatched = |border: u32| counts[&canonical(border, side)] == 1;
+
+    // a corner, turned so that its unmatched borders face outwards
+    let first = (0..tiles.len()).find_map(|tile| {
+        oriented[tile]
+            .iter()
+            .find(|(_, borders)| unmatched(borders[TOP]) && unmatched(borders[LEFT]))
+            .map(|&(orientation, _)| (tile, orientation))
+    });
+    let (tile, orientation) = if per_side == 1 {
+        (0, Orientation::IDENTITY)
+    } else {
+        first?
+    };
+
+    let mut placed = vec![Placement { tile, orientation }];
+    let mut used = vec![false; tiles.len()];
+    used[tile] = true;
+    let borders_of = |p: &Placement| {
+        oriented[p.tile]
+            .iter()
+            .find(|o| o.0 == p.orientation)
+            .unwrap()
+            .1
+    };
+
+    for i in 1..tiles.len() {
+        let left = (i % per_side > 0).then(|| borders_of(&placed[i - 1])[RIGHT]);
+        let up = (i >= per_side).then(|| borders_of(&placed[i - per_side])[BOTTOM]);
+
+        let next = (0..tiles.len())
+            .filter(|&tile| !used[tile])
+            .find_map(|tile| {
+                oriented[tile]
+                    .iter()
+                    .find(|(_, borders)| {
+                        left.is_none_or(|left| borders[LEFT] == left)
+                            && up.is_none_or(|up| borders[TOP] == up)
+                    })
+                    .map(|&(orientation, _)| Placement { tile, orientation })
+            })?;
+
+        used[next.tile] = true;
+        placed.push(next);
+    }
+
+    Some(Grid::from_fn(per_side, per_side, |pos| {
+        placed[pos.row * per_side + pos.col]
+    }))
+}
+
+/// The assembled image, with the border of every tile removed.
+pub fn image(tiles: &[Tile], layout: &Grid<Placement>) -> Grid<Pixel> {
+    let oriented: Vec<Grid<Pixel>> = layout
+        .iter()
+        .map(|p| tiles[p.tile].pixels.oriented(p.orientation))
+        .collect();
+    let inner = tiles[0].pixels.width() - 2;
+
+    Grid::from_fn(layout.width() * inner, layout.height() * inner, |pos| {
+        let tile = &oriented[(pos.row / inner) * layout.width() + pos.col / inner];
+        tile[Pos::new(pos.row % inner + 1, pos.col % inner + 1)]
+    })
+}
+
+/// A shape to look for in an image: the pixels that must be on, relative to its top left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    height: usize,
+    width: usize,
+    on: Vec<Pos>,
+}
+
+pub static SEA_MONSTER: &str = "
+                  #
+#    ##    ##    ###
+ #  #  #  #  #  #   ";
+
+impl Pattern {
+    /// Reads a pattern where `#` marks a pixel that must be on, and anything else can be
+    /// anything. Leading and trailing blank lines are ignored.
+    pub fn new(text: &str) -> Pattern {
+        let rows: Vec<&str> = text.trim_matches('\n').lines().collect();
+        let on = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, text)| {
+                text.chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c == '#')
+                    .map(move |(col, _)| Pos::new(row, col))
+            })
+            .collect();
+
+        Pattern {
+            height: rows.len(),
+            width: rows
+                .iter()
+                .map(|row| row.chars().count())
+                .max()
+                .unwrap_or(0),
+            on,
+        }
+    }
+
+    pub fn oriented(&self, orientation: Orientation) -> Pattern {
+        let (height, width) = orientation.dims((self.height, self.width));
+        Pattern {
+            height,
+            width,
+            on: self
+                .on
+                .iter()
+                .map(|&pos| orientation.apply(pos, (self.height, self.width)))
+                .collect(),
+        }
+    }
+
+    /// The pixels the pattern covers when its top left is at `at`.
+    pub fn cells(&self, at: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.on
+            .iter()
+            .map(move |pos| Pos::new(at.row + pos.row, at.col + pos.col))
+    }
+
+    /// The top left of every place the pattern appears in `image`, as it is.
+    pub fn find(&self, image: &Grid<Pixel>) -> Vec<Pos> {
+        if self.height > image.height() || self.width > image.width() {
+            return Vec::new();
+        }
+
+        (0..=image.height() - self.height)
+            .flat_map(|row| (0..=image.width() - self.width).map(move |col| Pos::new(row, col)))
+            .filter(|&at| self.cells(at).all(|pos| image[pos] == Pixel::On))
+            .collect()
+    }
+
+    /// Looks for the pattern turned and flipped every way, returning the orientation of the
+    /// pattern that appears most often in `image` along with where it does.
+    pub fn find_any_orientation(&self, image: &Grid<Pixel>) -> Option<(Pattern, Vec<Pos>)> {
+        Orientation::ALL
+            .iter()
+            .map(|&o| {
+                let pattern = self.oriented(o);
+                let found = pattern.find(image);
+                (pattern, found)
+            })
+            .filter(|(_, found)| !found.is_empty())
+            .max_by_key(|(_, found)| found.len())
+    }
+}
+
+/// How many pixels are on and not part of any appearance of `pattern`, in the orientation
+/// it appears in most.
+pub fn roughness(image: &Grid<Pixel>, pattern: &Pattern) -> usize {
+    let on = image.iter().filter(|&&p| p == Pixel::On).count();
+    let covered: HashSet<Pos> = match pattern.find_any_orientation(image) {
+        Some((pattern, found)) => found.iter().flat_map(|&at| pattern.cells(at)).collect(),
+        None => HashSet::new(),
+    };
+
+    on - covered.len()
+}
+
+#[aoc_generator(day20)]
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let mut lines = error::lines(20, input);
+    let mut tiles: Vec<Tile> = Vec::new();
+
+    while let Some(header) = lines.next() {
+        if header.text.is_empty() {
+            continue;
+        }
+
+        let id = header
+            .text
+            .strip_prefix("Tile ")
+            .and_then(|rest| rest.strip_suffix(':'))
+            .ok_or_else(|| header.error(header.text, "`Tile <id>:`"))?;
+        let id = header.parse(id, "a tile id")?;
+        let pixels = Grid::from_lines(lines.by_ref().take_while(|line| !line.text.is_empty()))?;
+
+        // the first tile sets the side for the rest, up to what a border's bits can hold
+        let expected = match tiles.first() {
+            Some(first) if pixels.width() != first.pixels.width() => Some(format!(
+                "a tile of {0}x{0} pixels after this",
+                first.pixels.width()
+            )),
+            None if !(3..=32).contains(&pixels.width()) => {
+                Some("a tile of 3 to 32 pixels a side after this".to_string())
+            }
+            _ if pixels.height() != pixels.width() => Some("a square tile after this".to_string()),
+            _ => None,
+        };
+        if let Some(expected) = expected {
+            return Err(header.error(header.text, expected));
+        }
+        tiles.push(Tile { id, pixels });
+    }
+
+    Ok(Input { tiles })
+}
+
+#[aoc(day20, part1)]
+pub fn part1(input: &Input) -> Result<u64, NoFit> {
+    let layout = input.assemble()?.layout;
+    let (last_row, last_col) = (layout.height() - 1, layout.width() - 1);
+
+    Ok([(0, 0), (0, last_col), (last_row, 0), (last_row, last_col)]
+        .iter()
+        .map(|&(row, col)| input.tiles[layout[Pos::new(row, col)].tile].id)
+        .product())
+}
+
+#[aoc(day20, part2)]
+pub fn part2(input: &Input) -> Result<usize, NoFit> {
+    Ok(roughness(
+        &input.assemble()?.image,
+        &Pattern::new(SEA_MONSTER),
+    ))
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u8 = 20;
+
+    type Input = Input;
+    type Output1 = Result<u64, NoFit>;
+    type Output2 = Result<usize, NoFit>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+Tile 2311:
+..##.#..#.
+##..#.....
+#...##..#.
+####.#...#
+##.##.###.
+##...#.###
+.#.#.#..##
+..#....#..
+###...#.#.
+..###..###
+
+Tile 1951:
+#.##...##.
+#.####...#
+.....#..##
+#...######
+.##.#....#
+.###.#####
+###.##.##.
+.###....#.
+..#.#..#.#
+#...##.#..
+
+Tile 1171:
+####...##.
+#..##.#..#
+##.#..#.#.
+.###.####.
+..###.####
+.##....##.
+.#...####.
+#.##.####.
+####..#...
+.....##...
+
+Tile 1427:
+###.##.#..
+.#..#.##..
+.#.##.#..#
+#.#.#.##.#
+....#...##
+...##..##.
+...#.#####
+.#.####.#.
+..#..###.#
+..##.#..#.
+
+Tile 1489:
+##.#.#....
+..##...#..
+.##..##...
+..#...#...
+#####...#.
+#..#.#.#.#
+...#.#.#..
+##.#...##.
+..##.##.##
+###.##.#..
+
+Tile 2473:
+#....####.
+#..#.##...
+#.##..#...
+######.#.#
+.#...#.#.#
+.#########
+.###.#..#.
+########.#
+##...##.#.
+..###.#.#.
+
+Tile 2971:
+..#.#....#
+#...###...
+#.#.###...
+##.##..#..
+.#####..##
+.#..####.#
+#..#.#..#.
+..####.###
+..#.#.###.
+...#.#.#.#
+
+Tile 2729:
+...#.#.#.#
+####.#....
+..#.#.....
+....#..#.#
+.##..##.#.
+.#.####...
+####.#.#..
+##.####...
+##..#.##..
+#.##...##.
+
+Tile 3079:
+#.#.#####.
+.#..######
+..#.......
+######....
+####.#..#.
+.#...#.##.
+#.#####.##
+..#.###...
+..#.......
+..#.###...";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), Ok(20899048083289));
+        assert_eq!(part2(&input), Ok(273));
+    }
+
+    #[test]
+    fn test_tile_sides() {
+        let input = parse("Tile 7:\n#..\n...\n..#").unwrap();
+        assert_eq!(part1(&input), Ok(7 * 7 * 7 * 7));
+
+        let err = parse("Tile 7:\n#.\n..").unwrap_err();
+        assert_eq!(err.expected, "a tile of 3 to 32 pixels a side after this");
+        let err = parse("Tile 7:\n#..\n...\n..#\n\nTile 8:\n#...\n....").unwrap_err();
+        assert_eq!(
+            (err.line, err.expected.as_str()),
+            (6, "a tile of 3x3 pixels after this")
+        );
+        let err = parse("Tile 7:\n#..\n...").unwrap_err();
+        assert_eq!(err.expected, "a square tile after this");
+
+        // nothing can go next to the first tile, whichever way they turn
+        let input = parse("Tile 7:\n#..\n...\n...\n\nTile 8:\n.#.\n.#.\n.#.").unwrap();
+        assert_eq!(part1(&input), Err(NoFit));
+    }
+
+    #[test]
+    fn test_pattern_search() {
+        let input = parse(EXAMPLE).unwrap();
+        let (_, found) = Pattern::new(SEA_MONSTER)
+            .find_any_orientation(&input.assemble().unwrap().image)
+            .unwrap();
+        assert_eq!(found.len(), 2);
+
+        let pattern = Pattern::new("#.#\n.#.");
+        assert_eq!((pattern.height, pattern.width, pattern.on.len()), (2, 3, 3));
+        let turned = pattern.oriented(Orientation::new(false, 1));
+        assert_eq!((turned.height, turned.width), (3, 2));
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use aoc_runner_derive::*;
+
+use crate::assignment::{self, AssignError};
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Food {
+    pub ingredients: Vec<String>,
+    pub allergens: Vec<String>,
+}
+
+#[aoc_generator(day21)]
+pub fn parse(input: &str) -> Result<Vec<Food>, ParseError> {
+    error::lines(21, input)
+        .map(|line| {
+            let (ingredients, allergens) = match line.text.find(" (contains ") {
+                Some(i) => {
+                    let list = &line.text[i + " (contains ".len()..];
+                    let list = list.strip_suffix(')').ok_or_else(|| line.end("`)`"))?;
+                    (&line.text[..i], list.split(", ").collect())
+                }
+                None => (line.text, Vec::new()),
+            };
+
+            let ingredients: Vec<&str> = ingredients.split_whitespace().collect();
+            if ingredients.is_empty() {
+                return Err(line.error(line.text, "at least one ingredient"));
+            }
+            for allergen in &allergens {
+                if allergen.is_empty() || allergen.contains(char::is_whitespace) {
+                    return Err(line.error(allergen, "an allergen"));
+                }
+            }
+
+            Ok(Food {
+                ingredients: ingredients.into_iter().map(String::from).collect(),
+                allergens: allergens.into_iter().map(String::from).collect(),
+            })
+        })
+        .collect()
+}
+
+/// For every allergen, the ingredients that appear in every food known to contain it.
+pub fn candidates(foods: &[Food]) -> BTreeMap<&str, BTreeSet<&str>> {
+    let mut candidates: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+
+    for food in foods {
+        let ingredients: BTreeSet<&str> = food.ingredients.iter().map(String::as_str).collect();
+        for allergen in &food.allergens {
+            candidates
+                .entry(allergen)
+                .and_modify(|set| set.retain(|i| ingredients.contains(i)))
+                .or_insert_with(|| ingredients.clone());
+        }
+    }
+
+    candidates
+}
+
+/// Which ingredient contains each allergen, if the foods narrow it down to one way.
+pub fn allergens(foods: &[Food]) -> Result<BTreeMap<&str, &str>, AssignError<&str, &str>> {
+    assignment::solve(candidates(foods))
+}
+
+#[aoc(day21, part1)]
+pub fn part1(input: &[Food]) -> usize {
+    let suspicious: BTreeSet<&str> = candidates(input).into_values().flatten().collect();
+
+    input
+        .iter()
+        .flat_map(|food| &food.ingredients)
+        .filter(|ingredient| !suspicious.contains(ingredient.as_str()))
+        .count()
+}
+
+#[aoc(day21, part2)]
+pub fn part2(input: &[Food]) -> String {
+    match allergens(input) {
+        Ok(allergens) => allergens.into_values().collect::<Vec<_>>().join(","),
+        Err(err) => panic!("the allergens cannot be worked out: {}", err),
+    }
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+
+    type Input = Vec<Food>;
+    type Output1 = usize;
+    type Output2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+mxmxvkd kfcds sqjhc nhms (contains dairy, fish)
+trh fvjkl sbzzf mxmxvkd (contains dairy)
+sqjhc fvjkl (contains soy)
+sqjhc mxmxvkd sbzzf (contains fish)";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), 5);
+        assert_eq!(part2(&input), "mxmxvkd,sqjhc,fvjkl");
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        let input = parse("a b (contains x, y)").unwrap();
+        let err = allergens(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "could not narrow down `x` (any of `a`, `b`); `y` (any of `a`, `b`)"
+        );
+
+        let input = parse("a (contains x)\nb (contains x)").unwrap();
+        assert_eq!(
+            allergens(&input),
+            Err(AssignError::Contradiction { key: "x" })
+        );
+    }
+}
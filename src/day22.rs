@@ -0,0 +1,371 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use aoc_runner_derive::*;
+
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Deck(VecDeque<u32>);
+
+impl Deck {
+    pub fn new(cards: impl IntoIterator<Item = u32>) -> Deck {
+        Deck(cards.into_iter().collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Takes the top card.
+    pub fn draw(&mut self) -> Option<u32> {
+        self.0.pop_front()
+    }
+
+    /// Puts the cards of a won round on the bottom, the winning card first.
+    pub fn collect(&mut self, winning: u32, losing: u32) {
+        self.0.push_back(winning);
+        self.0.push_back(losing);
+    }
+
+    /// A copy of the top `n` cards, to play a sub-game with.
+    pub fn top(&self, n: usize) -> Deck {
+        Deck(self.0.iter().take(n).copied().collect())
+    }
+
+    /// Each card times its position counting up from the bottom.
+    pub fn score(&self) -> u64 {
+        self.0
+            .iter()
+            .rev()
+            .zip(1..)
+            .map(|(&card, i)| u64::from(card) * i)
+            .sum()
+    }
+}
+
+impl fmt::Display for Deck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, card) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", card)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    fn index(self) -> usize {
+        match self {
+            Player::One => 0,
+            Player::Two => 1,
+        }
+    }
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "player {}", self.index() + 1)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rules {
+    Plain,
+    Recursive,
+}
+
+/// How a round was decided.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Higher(Player),
+    SubGame(Player),
+    /// The decks were the same as in an earlier round of the game, which ends it in
+    /// favour of player 1 without any cards being played.
+    Repeat,
+}
+
+impl Outcome {
+    pub fn winner(self) -> Player {
+        match self {
+            Outcome::Higher(player) | Outcome::SubGame(player) => player,
+            Outcome::Repeat => Player::One,
+        }
+    }
+}
+
+/// One round of one game, with the decks as they were before it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Round {
+    pub game: usize,
+    pub number: usize,
+    pub decks: [Deck; 2],
+    pub outcome: Outcome,
+}
+
+impl fmt::Display for Round {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "game {}, round {}: {} | {} -> ",
+            self.game, self.number, self.decks[0], self.decks[1]
+        )?;
+        match self.outcome {
+            Outcome::Higher(player) => write!(f, "{} wins the round", player),
+            Outcome::SubGame(player) => write!(f, "{} wins the round by a sub-game", player),
+            Outcome::Repeat => write!(f, "repeated, {} wins the game", Player::One),
+        }
+    }
+}
+
+#[aoc_generator(day22)]
+pub fn parse(input: &str) -> Result<[Deck; 2], ParseError> {
+    let mut decks = [Deck::default(), Deck::default()];
+    let mut dealt = HashSet::new();
+    let mut lines = error::lines(22, input)
+        .filter(|line| !line.text.is_empty())
+        .peekable();
+
+    for (i, deck) in decks.iter_mut().enumerate() {
+        let header = format!("Player {}:", i + 1);
+        match lines.next() {
+            Some(line) if line.text == header => {}
+            Some(line) => return Err(line.error(line.text, format!("`{}`", header))),
+            None => return Err(ParseError::end_of_input(22, input, format!("`{}`", header))),
+        }
+
+        while let Some(line) = lines.next_if(|line| !line.text.starts_with("Player")) {
+            let card = line.parse(line.text, "a card")?;
+            if !dealt.insert(card) {
+                return Err(line.error(line.text, "a card that has not been dealt yet"));
+            }
+            deck.0.push_back(card);
+        }
+    }
+
+    if let Some(line) = lines.next() {
+        return Err(line.error(line.text, "the end of the input"));
+    }
+
+    Ok(decks)
+}
+
+/// Plays a game to the end, returning who won and with what deck, and pushing every round
+/// played onto `log` if there is one. Sub-games that were already played with the same decks
+/// are not played again, so their rounds are only logged the first time.
+///
+/// Returns `None` if a game under the plain rules gets back to decks it had before, since it
+/// would never end.
+pub fn play(
+    decks: &[Deck; 2],
+    rules: Rules,
+    log: Option<&mut Vec<Round>>,
+) -> Option<(Player, Deck)> {
+    let mut game = Game {
+        rules,
+        log,
+        games: 0,
+        outcomes: HashMap::new(),
+    };
+
+    let (winner, mut decks) = game.play(decks.clone())?;
+    Some((winner, std::mem::take(&mut decks[winner.index()])))
+}
+
+struct Game<'a> {
+    rules: Rules,
+    log: Option<&'a mut Vec<Round>>,
+    games: usize,
+    // who won sub-games starting from the given decks
+    outcomes: HashMap<[Deck; 2], Player>,
+}
+
+impl Game<'_> {
+    fn play(&mut self, mut decks: [Deck; 2]) -> Option<(Player, [Deck; 2])> {
+        self.games += 1;
+        let game = self.games;
+        let mut seen = HashSet::new();
+
+        for number in 1.. {
+            if decks[1].is_empty() {
+                return Some((Player::One, decks));
+            } else if decks[0].is_empty() {
+                return Some((Player::Two, decks));
+            }
+
+            let repeated = !seen.insert(decks.clone());
+            if repeated && self.rules == Rules::Plain {
+                return None;
+            }
+
+            let before = self.log.is_some().then(|| decks.clone());
+            let outcome = if repeated {
+                Outcome::Repeat
+            } else {
+                self.round(&mut decks)?
+            };
+
+            if let (Some(log), Some(decks)) = (&mut self.log, before) {
+                log.push(Round {
+                    game,
+                    number,
+                    decks,
+                    outcome,
+                });
+            }
+            if repeated {
+                return Some((Player::One, decks));
+            }
+        }
+
+        unreachable!()
+    }
+
+    fn round(&mut self, decks: &mut [Deck; 2]) -> Option<Outcome> {
+        let cards = [decks[0].draw()?, decks[1].draw()?];
+
+        let recurse = self.rules == Rules::Recursive
+            && decks[0].len() >= cards[0] as usize
+            && decks[1].len() >= cards[1] as usize;
+
+        let outcome = if recurse {
+            let sub = [
+                decks[0].top(cards[0] as usize),
+                decks[1].top(cards[1] as usize),
+            ];
+            let winner = match self.outcomes.get(&sub) {
+                Some(&winner) => winner,
+                None => {
+                    let (winner, _) = self.play(sub.clone())?;
+                    self.outcomes.insert(sub, winner);
+                    winner
+                }
+            };
+            Outcome::SubGame(winner)
+        } else if cards[0] > cards[1] {
+            Outcome::Higher(Player::One)
+        } else {
+            Outcome::Higher(Player::Two)
+        };
+
+        let winner = outcome.winner().index();
+        decks[winner].collect(cards[winner], cards[1 - winner]);
+        Some(outcome)
+    }
+}
+
+fn score(decks: &[Deck; 2], rules: Rules) -> u64 {
+    let (_, deck) = play(decks, rules, None).expect("the game goes round in circles forever");
+    deck.score()
+}
+
+#[aoc(day22, part1)]
+pub fn part1(input: &[Deck; 2]) -> u64 {
+    score(input, Rules::Plain)
+}
+
+#[aoc(day22, part2)]
+pub fn part2(input: &[Deck; 2]) -> u64 {
+    score(input, Rules::Recursive)
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u8 = 22;
+
+    type Input = [Deck; 2];
+    type Output1 = u64;
+    type Output2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+Player 1:
+9
+2
+6
+3
+1
+
+Player 2:
+5
+8
+4
+7
+10";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), 306);
+        assert_eq!(part2(&input), 291);
+    }
+
+    #[test]
+    fn test_log() {
+        let input = parse(EXAMPLE).unwrap();
+
+        let mut log = Vec::new();
+        let (winner, deck) = play(&input, Rules::Plain, Some(&mut log)).unwrap();
+        assert_eq!(
+            (winner, deck.to_string()),
+            (Player::Two, "3, 2, 10, 6, 8, 5, 9, 4, 7, 1".into())
+        );
+        assert_eq!(log.len(), 29);
+        assert_eq!(
+            log[0].to_string(),
+            "game 1, round 1: 9, 2, 6, 3, 1 | 5, 8, 4, 7, 10 -> player 1 wins the round"
+        );
+
+        let mut log = Vec::new();
+        play(&input, Rules::Recursive, Some(&mut log)).unwrap();
+        assert_eq!(log.iter().filter(|round| round.game == 1).count(), 17);
+        let round = log
+            .iter()
+            .find(|round| (round.game, round.number) == (1, 9));
+        assert_eq!(
+            round.unwrap().to_string(),
+            "game 1, round 9: 4, 9, 8, 5, 2 | 3, 10, 1, 7, 6 -> player 2 wins the round by a sub-game"
+        );
+    }
+
+    #[test]
+    fn test_repeat() {
+        let input = parse("Player 1:\n43\n19\n\nPlayer 2:\n2\n29\n14").unwrap();
+        assert_eq!(play(&input, Rules::Plain, None), None);
+
+        let mut log = Vec::new();
+        let (winner, _) = play(&input, Rules::Recursive, Some(&mut log)).unwrap();
+        assert_eq!(winner, Player::One);
+        assert_eq!(log.last().unwrap().outcome, Outcome::Repeat);
+    }
+}
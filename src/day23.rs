@@ -0,0 +1,178 @@
+use aoc_runner_derive::*;
+
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
+#[aoc_generator(day23)]
+pub fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+    let line = match error::lines(23, input).next() {
+        Some(line) => line,
+        None => return Err(ParseError::end_of_input(23, input, "the cup labels")),
+    };
+
+    let mut labels = Vec::new();
+    for (i, c) in line.text.char_indices() {
+        match c.to_digit(10) {
+            Some(label) if label > 0 && !labels.contains(&label) => labels.push(label),
+            _ => return Err(line.char_error(i, "a cup label that isn't taken yet")),
+        }
+    }
+
+    let len = labels.len() as u32;
+    if len < 5 {
+        return Err(line.end("at least five cups"));
+    } else if let Some(missing) = (1..=len).find(|label| !labels.contains(label)) {
+        return Err(line.end(format!("cup {}", missing)));
+    }
+    Ok(labels)
+}
+
+/// A circle of cups labelled from 1, stored as the label of the cup clockwise of each one.
+#[derive(Clone, Debug)]
+pub struct Cups {
+    // `next[0]` is unused
+    next: Vec<u32>,
+    current: u32,
+}
+
+impl Cups {
+    /// Lays out `labels` clockwise, followed by the rest of the cups up to `total` in order.
+    /// The first cup is the current one. Moves need at least five cups to make sense.
+    pub fn new(labels: &[u32], total: u32) -> Cups {
+        let order: Vec<u32> = labels
+            .iter()
+            .copied()
+            .chain(labels.len() as u32 + 1..=total)
+            .collect();
+
+        let mut next = vec![0; order.len() + 1];
+        for (i, &label) in order.iter().enumerate() {
+            next[label as usize] = order[(i + 1) % order.len()];
+        }
+
+        Cups {
+            next,
+            current: order[0],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.next.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The label of the cup clockwise of `label`.
+    pub fn next(&self, label: u32) -> u32 {
+        self.next[label as usize]
+    }
+
+    /// Picks up the three cups after the current one, puts them back after the cup labelled
+    /// one less (skipping the ones picked up, and wrapping around), and moves on clockwise.
+    pub fn play(&mut self, moves: usize) {
+        let max = self.len() as u32;
+
+        for _ in 0..moves {
+            let first = self.next(self.current);
+            let second = self.next(first);
+            let third = self.next(second);
+
+            let mut destination = self.current;
+            loop {
+                destination = if destination == 1 {
+                    max
+                } else {
+                    destination - 1
+                };
+                if destination != first && destination != second && destination != third {
+                    break;
+                }
+            }
+
+            self.next[self.current as usize] = self.next(third);
+            self.next[third as usize] = self.next(destination);
+            self.next[destination as usize] = first;
+            self.current = self.next(self.current);
+        }
+    }
+
+    /// Goes once around the circle clockwise, starting at `label`.
+    pub fn iter_from(&self, label: u32) -> impl Iterator<Item = u32> + '_ {
+        std::iter::successors(Some(label), move |&label| Some(self.next(label))).take(self.len())
+    }
+}
+
+#[aoc(day23, part1)]
+pub fn part1(input: &[u32]) -> String {
+    let mut cups = Cups::new(input, input.len() as u32);
+    cups.play(100);
+    cups.iter_from(1)
+        .skip(1)
+        .map(|label| label.to_string())
+        .collect()
+}
+
+#[aoc(day23, part2)]
+pub fn part2(input: &[u32]) -> u64 {
+    let mut cups = Cups::new(input, 1_000_000);
+    cups.play(10_000_000);
+    cups.iter_from(1).skip(1).take(2).map(u64::from).product()
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u8 = 23;
+
+    type Input = Vec<u32>;
+    type Output1 = String;
+    type Output2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "389125467";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+
+        let mut cups = Cups::new(&input, 9);
+        cups.play(10);
+        let circle: Vec<u32> = cups.iter_from(1).collect();
+        assert_eq!(circle, [1, 9, 2, 6, 5, 8, 3, 7, 4]);
+
+        assert_eq!(part1(&input), "67384529");
+        assert_eq!(part2(&input), 149245887792);
+    }
+
+    #[test]
+    fn test_iter_from() {
+        let cups = Cups::new(&[3, 1, 2], 5);
+        assert_eq!(cups.iter_from(2).collect::<Vec<_>>(), [2, 4, 5, 3, 1]);
+        assert_eq!(cups.iter_from(5).count(), 5);
+    }
+
+    #[test]
+    fn test_bad_labels() {
+        assert_eq!(parse("385612").unwrap_err().expected, "cup 4");
+        assert_eq!(parse("3856123").unwrap_err().column, 7);
+        assert_eq!(parse("3812").unwrap_err().expected, "at least five cups");
+    }
+}
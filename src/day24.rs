@@ -0,0 +1,140 @@
+use std::collections::{HashMap, HashSet};
+
+use aoc_runner_derive::*;
+
+use crate::error::{self, ParseError};
+use crate::hex::{self, Hex, HexDir};
+use crate::solution::Solution;
+
+/// An infinite floor of hexagonal tiles, of which only the black ones are stored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Floor {
+    black: HashSet<Hex>,
+}
+
+impl Floor {
+    /// Flips the tile at the end of each path from the origin.
+    pub fn from_paths(paths: &[Vec<HexDir>]) -> Floor {
+        let mut black = HashSet::new();
+        for path in paths {
+            let tile = path.iter().fold(Hex::ORIGIN, |hex, &dir| hex + dir);
+            if !black.remove(&tile) {
+                black.insert(tile);
+            }
+        }
+
+        Floor { black }
+    }
+
+    pub fn len(&self) -> usize {
+        self.black.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.black.is_empty()
+    }
+
+    pub fn is_black(&self, hex: Hex) -> bool {
+        self.black.contains(&hex)
+    }
+
+    /// Runs one day: a black tile stays black with 1 or 2 black neighbors,
+    /// a white one turns black with exactly 2.
+    pub fn step(&self) -> Floor {
+        let mut neighbors: HashMap<Hex, u8> = HashMap::new();
+        for tile in &self.black {
+            for neighbor in tile.neighbors() {
+                *neighbors.entry(neighbor).or_insert(0) += 1;
+            }
+        }
+
+        let black = neighbors
+            .into_iter()
+            .filter(|&(tile, count)| count == 2 || (count == 1 && self.is_black(tile)))
+            .map(|(tile, _)| tile)
+            .collect();
+
+        Floor { black }
+    }
+
+    pub fn run(&self, days: usize) -> Floor {
+        (0..days).fold(self.clone(), |floor, _| floor.step())
+    }
+}
+
+#[aoc_generator(day24)]
+pub fn parse(input: &str) -> Result<Vec<Vec<HexDir>>, ParseError> {
+    error::lines(24, input)
+        .map(|line| hex::parse_steps(&line))
+        .collect()
+}
+
+#[aoc(day24, part1)]
+pub fn part1(input: &[Vec<HexDir>]) -> usize {
+    Floor::from_paths(input).len()
+}
+
+#[aoc(day24, part2)]
+pub fn part2(input: &[Vec<HexDir>]) -> usize {
+    Floor::from_paths(input).run(100).len()
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u8 = 24;
+
+    type Input = Vec<Vec<HexDir>>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+sesenwnenenewseeswwswswwnenewsewsw
+neeenesenwnwwswnenewnwwsewnenwseswesw
+seswneswswsenwwnwse
+nwnwneseeswswnenewneswwnewseswneseene
+swweswneswnenwsewnwneneseenw
+eesenwseswswnenwswnwnwsewwnwsene
+sewnenenenesenwsewnenwwwse
+wenwwweseeeweswwwnwwe
+wsweesenenewnwwnwsenewsenwwsesesenwne
+neeswseenwwswnwswswnw
+nenwswwsewswnenenewsenwsenwnesesenew
+enewnwewneswsewnwswenweswnenwsenwsw
+sweneswneswneneenwnewenewwneswswnese
+swwesenesewenwneswnwwneseswwne
+enesenwswwswneneswsenwnewswseenwsese
+wnwnesenesenenwwnenwsewesewsesesew
+nenewswnwewswnenesenwnesewesw
+eneswnwswnwsenenwnwnwwseeswneewsenese
+neswnwewnwnwseenwseesewsenwsweewe
+wseweeenwnesenwwwswnew";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), 10);
+        assert_eq!(part2(&input), 2208);
+
+        let floor = Floor::from_paths(&input);
+        assert_eq!(floor.step().len(), 15);
+        assert_eq!(floor.run(10).len(), 37);
+    }
+}
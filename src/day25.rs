@@ -0,0 +1,79 @@
+use std::convert::Infallible;
+
+use aoc_runner_derive::*;
+
+use crate::error::{self, ParseError};
+use crate::number;
+use crate::solution::Solution;
+
+pub const MODULUS: u64 = 20201227;
+pub const SUBJECT: u64 = 7;
+
+/// The public keys of the card and the door.
+pub type Keys = (u64, u64);
+
+#[aoc_generator(day25)]
+pub fn parse(input: &str) -> Result<Keys, ParseError> {
+    let mut keys = Vec::new();
+    for line in error::lines(25, input).take(2) {
+        let key: u64 = line.parse(line.text, "a public key")?;
+        if key == 0 || key >= MODULUS {
+            return Err(line.error(line.text, format!("a public key below {}", MODULUS)));
+        }
+        keys.push(key);
+    }
+
+    match keys[..] {
+        [card, door] => Ok((card, door)),
+        _ => Err(ParseError::end_of_input(25, input, "a public key")),
+    }
+}
+
+/// How many times the subject number was transformed to get `public_key`.
+pub fn loop_size(public_key: u64) -> Option<u64> {
+    number::discrete_log(SUBJECT, public_key, MODULUS)
+}
+
+#[aoc(day25, part1)]
+pub fn part1(input: &Keys) -> u64 {
+    let (card, door) = *input;
+    let card_loop = loop_size(card).expect("the card's public key is never reached");
+
+    number::mod_pow(door, card_loop, MODULUS)
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u8 = 25;
+    const PARTS: u8 = 1;
+
+    type Input = Keys;
+    type Output1 = u64;
+    type Output2 = Infallible;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(_: &Self::Input) -> Self::Output2 {
+        panic!("day 25 has no part 2")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = parse("5764801\n17807724").unwrap();
+        assert_eq!(loop_size(input.0), Some(8));
+        assert_eq!(loop_size(input.1), Some(11));
+        assert_eq!(part1(&input), 14897079);
+    }
+}
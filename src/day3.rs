@@ -1,46 +1,110 @@
+use std::iter;
+
 use aoc_runner_derive::*;
 
-type TreeMatrix = [Vec<bool>];
+use crate::error::ParseError;
+use crate::grid::{Cell, Dir, Grid, Pos, Wrap};
+use crate::solution::Solution;
 
-#[aoc_generator(day3)]
-fn generate_tree_matrix(input: &str) -> Vec<Vec<bool>> {
-    let mut matrix = Vec::new();
-    for line in input.lines() {
-        matrix.push(Vec::new());
-        let row = matrix.last_mut().unwrap();
-        for c in line.chars() {
-            row.push(c == '#');
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Square {
+    Open,
+    Tree,
+}
+
+impl Cell for Square {
+    const EXPECTED: &'static str = "`.` or `#`";
+
+    fn from_char(c: char) -> Option<Square> {
+        match c {
+            '.' => Some(Square::Open),
+            '#' => Some(Square::Tree),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Square::Open => '.',
+            Square::Tree => '#',
         }
     }
+}
 
-    matrix
+#[aoc_generator(day3)]
+pub fn parse(input: &str) -> Result<Grid<Square>, ParseError> {
+    Ok(Grid::parse(3, input)?.with_wrap(Wrap::Horizontal))
 }
 
 #[aoc(day3, part1)]
-fn solve_part1(input: &TreeMatrix) -> u32 {
+pub fn part1(input: &Grid<Square>) -> usize {
     count_trees_slope(input, 1, 3)
 }
 
-fn count_trees_slope(input: &TreeMatrix, down: usize, right: usize) -> u32 {
-    let mut trees = 0;
-    let mut i = 0;
-    let mut j = 0;
-
-    while i < input.len() {
-        if input[i][j] {
-            trees += 1;
-        }
-        i += down;
-        j = (j + right) % input[0].len();
+fn count_trees_slope(input: &Grid<Square>, down: isize, right: isize) -> usize {
+    let start = Pos::new(0, 0);
+    if input.get(start).is_none() {
+        return 0;
     }
-    trees
+
+    iter::once(start)
+        .chain(input.ray(start, Dir::new(down, right)))
+        .filter(|&pos| input[pos] == Square::Tree)
+        .count()
 }
 
 #[aoc(day3, part2)]
-fn solve_part2(input: &TreeMatrix) -> u32 {
+pub fn part2(input: &Grid<Square>) -> usize {
     count_trees_slope(input, 1, 1)
         * count_trees_slope(input, 1, 3)
         * count_trees_slope(input, 1, 5)
         * count_trees_slope(input, 1, 7)
         * count_trees_slope(input, 2, 1)
 }
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Grid<Square>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), 7);
+        assert_eq!(part2(&input), 336);
+    }
+}
@@ -2,11 +2,14 @@ use std::collections::HashMap;
 
 use aoc_runner_derive::*;
 
+use crate::error::{self, Line, ParseError};
+use crate::solution::Solution;
+
 use PassportAttribute::*;
-type Passport = HashMap<PassportAttribute, String>;
+pub type Passport = HashMap<PassportAttribute, String>;
 
 #[derive(PartialEq, Eq, Hash, Debug)]
-enum PassportAttribute {
+pub enum PassportAttribute {
     BirthYear,
     IssueYear,
     ExpirationYear,
@@ -29,7 +32,7 @@ macro_rules! handle_year {
 
 macro_rules! parse_or_return {
     ($e:expr, $ret:expr) => {
-        match u16::from_str_radix($e, 10) {
+        match $e.parse::<u16>() {
             Ok(v) => v,
             Err(_) => return $ret,
         }
@@ -60,26 +63,26 @@ impl PassportAttribute {
                 u32::from_str_radix(&val[1..], 16).is_ok()
             }
             EyeColor => EYE_COLORS.contains(&val),
-            PassportID => val.len() == 9 && u32::from_str_radix(&val, 10).is_ok(),
+            PassportID => val.len() == 9 && val.parse::<u32>().is_ok(),
             CountryID => true,
         }
     }
 }
 
 #[aoc_generator(day4)]
-fn generate(input: &str) -> Vec<Passport> {
+pub fn parse(input: &str) -> Result<Vec<Passport>, ParseError> {
     let mut output = Vec::new();
 
     let mut current = Passport::new();
-    for line in input.lines() {
-        if line.is_empty() {
+    for line in error::lines(4, input) {
+        if line.text.is_empty() {
             output.push(current);
             current = Passport::new();
             continue;
         }
 
-        for attr in line.split(' ') {
-            let (attr, val) = parse_attribute(attr).expect("Passport attribute parsing error.");
+        for attr in line.text.split(' ') {
+            let (attr, val) = parse_attribute(line, attr)?;
             current.insert(attr, val);
         }
     }
@@ -87,12 +90,14 @@ fn generate(input: &str) -> Vec<Passport> {
         output.push(current);
     }
 
-    output
+    Ok(output)
 }
 
-fn parse_attribute(attr: &str) -> Option<(PassportAttribute, String)> {
-    let mut splut = attr.split(':');
-    let (first, second) = (splut.next()?, splut.next()?);
+fn parse_attribute(line: Line, attr: &str) -> Result<(PassportAttribute, String), ParseError> {
+    let (first, second) = match attr.split_once(':') {
+        Some(val) => val,
+        None => return Err(line.error(attr, "`key:value`")),
+    };
 
     let first = match first {
         "byr" => BirthYear,
@@ -104,14 +109,14 @@ fn parse_attribute(attr: &str) -> Option<(PassportAttribute, String)> {
         "pid" => PassportID,
         "cid" => CountryID,
 
-        _ => return None,
+        _ => return Err(line.error(first, "`byr|iyr|eyr|hgt|hcl|ecl|pid|cid`")),
     };
 
-    Some((first, second.to_string()))
+    Ok((first, second.to_string()))
 }
 
 #[aoc(day4, part1)]
-fn solve_part1(input: &[Passport]) -> usize {
+pub fn part1(input: &[Passport]) -> usize {
     input.iter().filter(|x| is_valid_passport(x)).count()
 }
 
@@ -136,7 +141,7 @@ fn is_valid_passport(passport: &Passport) -> bool {
 }
 
 #[aoc(day4, part2)]
-fn solve_part2(input: &[Passport]) -> usize {
+pub fn part2(input: &[Passport]) -> usize {
     input.iter().filter(|x| is_valid_passport_strict(x)).count()
 }
 
@@ -154,3 +159,98 @@ fn is_valid_passport_strict(passport: &Passport) -> bool {
 
     true
 }
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Vec<Passport>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1_example() {
+        let input = parse(
+            "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in",
+        )
+        .unwrap();
+
+        assert_eq!(part1(&input), 2);
+    }
+
+    #[test]
+    fn test_part2_invalid_examples() {
+        let input = parse(
+            "\
+eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+iyr:2019
+hcl:#602927 eyr:1967 hgt:170cm
+ecl:grn pid:012533040 byr:1946
+
+hcl:dab227 iyr:2012
+ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277
+
+hgt:59cm ecl:zzz
+eyr:2038 hcl:74454a iyr:2023
+pid:3556412378 byr:2007",
+        )
+        .unwrap();
+
+        assert_eq!(part2(&input), 0);
+    }
+
+    #[test]
+    fn test_part2_valid_examples() {
+        let input = parse(
+            "\
+pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f
+
+eyr:2029 ecl:blu cid:129 byr:1989
+iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm
+
+hcl:#888785
+hgt:164cm byr:2001 iyr:2015 cid:88
+pid:545766238 ecl:hzl
+eyr:2022
+
+iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719",
+        )
+        .unwrap();
+
+        assert_eq!(part2(&input), 4);
+    }
+}
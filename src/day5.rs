@@ -1,6 +1,9 @@
 use aoc_runner_derive::*;
 
-struct Ticket {
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
+pub struct Ticket {
     row: [bool; 7],
     col: [bool; 3],
 }
@@ -50,32 +53,43 @@ impl Ticket {
 }
 
 #[aoc_generator(day5)]
-fn generate(input: &str) -> Vec<Ticket> {
+pub fn parse(input: &str) -> Result<Vec<Ticket>, ParseError> {
     let mut output = Vec::new();
 
-    for line in input.lines() {
-        let mut line = line.chars();
+    for line in error::lines(5, input) {
+        let mut chars = line.text.char_indices();
+        let mut next_half = |upper: char, lower: char| match chars.next() {
+            Some((_, c)) if c == upper => Ok(true),
+            Some((_, c)) if c == lower => Ok(false),
+            Some((i, _)) => Err(line.char_error(i, format!("`{}` or `{}`", upper, lower))),
+            None => Err(line.end(format!("`{}` or `{}`", upper, lower))),
+        };
+
         let mut temp = Ticket::new();
         for i in 0..7 {
-            temp.row[i] = line.next().unwrap() == 'B';
+            temp.row[i] = next_half('B', 'F')?;
         }
 
         for i in 0..3 {
-            temp.col[i] = line.next().unwrap() == 'R';
+            temp.col[i] = next_half('R', 'L')?;
+        }
+
+        if let Some((i, _)) = chars.next() {
+            return Err(line.error(&line.text[i..], "end of line"));
         }
         output.push(temp);
     }
 
-    output
+    Ok(output)
 }
 
 #[aoc(day5, part1)]
-fn solve_part1(input: &[Ticket]) -> usize {
+pub fn part1(input: &[Ticket]) -> usize {
     input.iter().map(|x| x.id()).max().unwrap()
 }
 
 #[aoc(day5, part2)]
-fn solve_part2(input: &[Ticket]) -> usize {
+pub fn part2(input: &[Ticket]) -> usize {
     let sorted = {
         let mut temp = input.iter().map(|x| x.id()).collect::<Vec<usize>>();
         temp.sort_unstable();
@@ -91,3 +105,47 @@ fn solve_part2(input: &[Ticket]) -> usize {
     }
     unreachable!()
 }
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = Vec<Ticket>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1_example() {
+        let input = parse("FBFBBFFRLR\nBFFFBBFRRR\nFFFBBBFRRR\nBBFFBBFRLL").unwrap();
+        let ids: Vec<usize> = input.iter().map(Ticket::id).collect();
+
+        assert_eq!((input[0].row_num(), input[0].col_num()), (44, 5));
+        assert_eq!(ids, vec![357, 567, 119, 820]);
+        assert_eq!(part1(&input), 820);
+    }
+
+    #[test]
+    fn test_part2() {
+        // the puzzle gives no example for part 2, so leave a gap at 357
+        let input = parse("FBFBBFFRLL\nFBFBBFFRRL").unwrap();
+        assert_eq!(part2(&input), 357);
+    }
+}
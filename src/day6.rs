@@ -1,22 +1,28 @@
 use aoc_runner_derive::*;
 use std::collections::HashSet;
 
-type Person = HashSet<char>;
-type Group = Vec<Person>;
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
+pub type Person = HashSet<char>;
+pub type Group = Vec<Person>;
 
 #[aoc_generator(day6)]
-fn generate(input: &str) -> Vec<Group> {
+pub fn parse(input: &str) -> Result<Vec<Group>, ParseError> {
     let mut output = Vec::new();
     let mut current_group = Group::new();
 
-    for line in input.lines() {
-        if line.is_empty() {
+    for line in error::lines(6, input) {
+        if line.text.is_empty() {
             output.push(current_group);
             current_group = Group::new();
             continue;
         }
         let mut current_person = Person::new();
-        for c in line.chars() {
+        for (i, c) in line.text.char_indices() {
+            if !c.is_ascii_lowercase() {
+                return Err(line.char_error(i, "a question from `a` to `z`"));
+            }
             current_person.insert(c);
         }
         current_group.push(current_person);
@@ -25,11 +31,11 @@ fn generate(input: &str) -> Vec<Group> {
         output.push(current_group);
     }
 
-    output
+    Ok(output)
 }
 
 #[aoc(day6, part1)]
-fn solve_part1(input: &[Group]) -> usize {
+pub fn part1(input: &[Group]) -> usize {
     let mut count = 0;
 
     for g in input {
@@ -43,7 +49,7 @@ fn solve_part1(input: &[Group]) -> usize {
 }
 
 #[aoc(day6, part2)]
-fn solve_part2(input: &[Group]) -> usize {
+pub fn part2(input: &[Group]) -> usize {
     let mut count = 0;
 
     for g in input {
@@ -56,3 +62,54 @@ fn solve_part2(input: &[Group]) -> usize {
     }
     count
 }
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = Vec<Group>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), 11);
+        assert_eq!(part2(&input), 6);
+    }
+}
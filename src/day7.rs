@@ -2,62 +2,71 @@ use std::collections::HashMap;
 
 use aoc_runner_derive::*;
 
+use crate::error::{self, Line, ParseError};
+use crate::solution::Solution;
+
 #[derive(Debug)]
-struct Bag {
+pub struct Bag {
     description: String,
     contains: Vec<(usize, String)>,
 }
 
 impl Bag {
-    fn parse(from: &str) -> Bag {
-        let mut s = from.split(" contain ");
-        let part1 = s.next().unwrap();
-        let part2 = s.next().unwrap();
-
-        let desc = {
-            let mut part1_spaces = part1.split(' ');
-            format!(
-                "{} {}",
-                part1_spaces.next().unwrap(),
-                part1_spaces.next().unwrap()
-            )
+    fn parse(line: Line) -> Result<Bag, ParseError> {
+        let (part1, part2) = match line.text.split_once(" contain ") {
+            Some(val) => val,
+            None => return Err(line.end("` contain `")),
         };
 
+        let desc = parse_description(line, part1)?;
+
         let mut contains = Vec::new();
-        for bag in part2.split(", ") {
-            let mut spaces = bag.split(' ');
-            let num = match usize::from_str_radix(spaces.next().unwrap(), 10) {
-                Ok(val) => val,
-                Err(_) => break,
-            };
-            let desc = format!("{} {}", spaces.next().unwrap(), spaces.next().unwrap());
-
-            contains.push((num, desc));
+        let part2 = part2.strip_suffix('.').unwrap_or(part2);
+        if part2 != "no other bags" {
+            for bag in part2.split(", ") {
+                let mut spaces = bag.splitn(2, ' ');
+                let num = line.parse(line.take(spaces.next(), "a bag count")?, "a bag count")?;
+                let desc = parse_description(line, line.take(spaces.next(), "a bag description")?)?;
+
+                contains.push((num, desc));
+            }
         }
 
-        Bag {
+        Ok(Bag {
             description: desc,
             contains,
-        }
+        })
     }
 }
 
-type Bags = HashMap<String, Bag>;
+fn parse_description(line: Line, bag: &str) -> Result<String, ParseError> {
+    let mut spaces = bag.split(' ');
+    let adjective = line.take(spaces.next(), "a bag description")?;
+    let color = line.take(spaces.next(), "a bag color")?;
+
+    match spaces.next() {
+        Some("bag") | Some("bags") => Ok(format!("{} {}", adjective, color)),
+        Some(other) => Err(line.error(other, "`bag` or `bags`")),
+        None => Err(line.end("`bag` or `bags`")),
+    }
+}
+
+pub type Bags = HashMap<String, Bag>;
 
 #[aoc_generator(day7)]
-fn generate(input: &str) -> Bags {
+pub fn parse(input: &str) -> Result<Bags, ParseError> {
     let mut bags = HashMap::new();
 
-    for line in input.lines() {
-        let bag = Bag::parse(line);
+    for line in error::lines(7, input) {
+        let bag = Bag::parse(line)?;
         bags.insert(bag.description.clone(), bag);
     }
 
-    bags
+    Ok(bags)
 }
 
 #[aoc(day7, part1)]
-fn solve_part1(input: &Bags) -> usize {
+pub fn part1(input: &Bags) -> usize {
     let mut results: HashMap<String, bool> = HashMap::new();
     let mut count = 0;
 
@@ -88,7 +97,7 @@ fn contains_shiny_gold(bag: &Bag, input: &Bags, results: &mut HashMap<String, bo
 }
 
 #[aoc(day7, part2)]
-fn solve_part2(input: &Bags) -> usize {
+pub fn part2(input: &Bags) -> usize {
     let mut results: HashMap<String, usize> = HashMap::new();
     count_inner_bags(input.get("shiny gold").unwrap(), input, &mut results)
 }
@@ -107,3 +116,65 @@ fn count_inner_bags(bag: &Bag, input: &Bags, results: &mut HashMap<String, usize
     results.insert(bag.description.clone(), res);
     res
 }
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = Bags;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), 4);
+        assert_eq!(part2(&input), 32);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let input = parse(
+            "\
+shiny gold bags contain 2 dark red bags.
+dark red bags contain 2 dark orange bags.
+dark orange bags contain 2 dark yellow bags.
+dark yellow bags contain 2 dark green bags.
+dark green bags contain 2 dark blue bags.
+dark blue bags contain 2 dark violet bags.
+dark violet bags contain no other bags.",
+        )
+        .unwrap();
+
+        assert_eq!(part2(&input), 126);
+    }
+}
@@ -2,36 +2,39 @@ use std::collections::HashSet;
 
 use aoc_runner_derive::*;
 
+use crate::error::{self, ParseError};
+use crate::solution::{Answer, Solution};
+
 use Instruction::*;
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Copy, Clone)]
-enum Instruction {
+pub enum Instruction {
     ACC(i32),
     JMP(i32),
     NOP(i32),
 }
 
 #[aoc_generator(day8)]
-fn generate(input: &str) -> Vec<Instruction> {
-    input
-        .lines()
-        .map(|x| {
-            let mut s = x.split(' ');
-            let part1 = s.next().unwrap();
-            let part2 = s.next().unwrap();
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    error::lines(8, input)
+        .map(|line| {
+            let mut s = line.text.split(' ');
+            let part1 = line.take(s.next(), "an instruction")?;
+            let part2 = line.take(s.next(), "an argument")?;
 
-            let num = i32::from_str_radix(part2, 10).unwrap();
+            let num = line.parse(part2, "a signed number")?;
 
-            match part1 {
+            Ok(match part1 {
                 "acc" => ACC(num),
                 "jmp" => JMP(num),
                 "nop" => NOP(num),
-                _ => unreachable!(),
-            }
+                _ => return Err(line.error(part1, "`acc|jmp|nop`")),
+            })
         })
         .collect()
 }
 
-struct Helper(bool, i32);
+pub struct Helper(pub bool, pub i32);
 
 impl std::fmt::Display for Helper {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -39,8 +42,14 @@ impl std::fmt::Display for Helper {
     }
 }
 
+impl Answer for Helper {
+    fn into_answer(self) -> Result<String, String> {
+        Ok(self.to_string())
+    }
+}
+
 #[aoc(day8, part1)]
-fn solve_part1(input: &[Instruction]) -> Helper {
+pub fn part1(input: &[Instruction]) -> Helper {
     let mut acc = 0;
     let mut i = 0;
     let mut encountered = HashSet::new();
@@ -64,7 +73,7 @@ fn solve_part1(input: &[Instruction]) -> Helper {
 }
 
 #[aoc(day8, part2)]
-fn solve_part2(input: &[Instruction]) -> i32 {
+pub fn part2(input: &[Instruction]) -> i32 {
     let mut copy = input.to_vec();
 
     for i in 0..copy.len() {
@@ -74,7 +83,7 @@ fn solve_part2(input: &[Instruction]) -> i32 {
             JMP(num) => copy[i] = NOP(num),
             NOP(num) => copy[i] = JMP(num),
         }
-        if let Helper(true, acc) = solve_part1(&copy) {
+        if let Helper(true, acc) = part1(&copy) {
             return acc;
         }
         copy[i] = prev;
@@ -82,3 +91,51 @@ fn solve_part2(input: &[Instruction]) -> i32 {
 
     unreachable!()
 }
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Vec<Instruction>;
+    type Output1 = Helper;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        let Helper(terminated, acc) = part1(&input);
+
+        assert!(!terminated);
+        assert_eq!(acc, 5);
+        assert_eq!(part2(&input), 8);
+    }
+}
@@ -2,21 +2,32 @@ use std::collections::HashSet;
 
 use aoc_runner_derive::*;
 
+use crate::error::{self, ParseError};
+use crate::solution::Solution;
+
 #[aoc_generator(day9)]
-fn generate(input: &str) -> Vec<u64> {
-    input.lines().map(|x| x.parse().unwrap()).collect()
+pub fn parse(input: &str) -> Result<Vec<u64>, ParseError> {
+    error::lines(9, input)
+        .map(|line| line.parse(line.text, "a number"))
+        .collect()
 }
 
+const PREAMBLE: usize = 25;
+
 #[aoc(day9, part1)]
-fn solve_part1(input: &[u64]) -> u64 {
+pub fn part1(input: &[u64]) -> u64 {
+    find_invalid(input, PREAMBLE)
+}
+
+fn find_invalid(input: &[u64], preamble: usize) -> u64 {
     let mut latest_nums: HashSet<u64> = HashSet::new();
 
-    latest_nums.extend(&input[..25]);
-    for i in 25..input.len() {
+    latest_nums.extend(&input[..preamble]);
+    for i in preamble..input.len() {
         if !check_sum(&latest_nums, input[i]) {
             return input[i];
         }
-        latest_nums.remove(&input[i - 25]);
+        latest_nums.remove(&input[i - preamble]);
         latest_nums.insert(input[i]);
     }
     unreachable!()
@@ -36,8 +47,12 @@ fn check_sum(latest: &HashSet<u64>, num: u64) -> bool {
 }
 
 #[aoc(day9, part2)]
-fn solve_part2(input: &[u64]) -> u64 {
-    let invalid_num = solve_part1(input);
+pub fn part2(input: &[u64]) -> u64 {
+    find_weakness(input, PREAMBLE)
+}
+
+fn find_weakness(input: &[u64], preamble: usize) -> u64 {
+    let invalid_num = find_invalid(input, preamble);
     for i in 0..input.len() {
         let mut sum = input[i];
 
@@ -50,3 +65,59 @@ fn solve_part2(input: &[u64]) -> u64 {
     }
     unreachable!()
 }
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<u64>;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+35
+20
+15
+25
+47
+40
+62
+55
+65
+95
+102
+117
+150
+182
+127
+219
+299
+277
+309
+576";
+
+    #[test]
+    fn test_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(find_invalid(&input, 5), 127);
+        assert_eq!(find_weakness(&input, 5), 62);
+    }
+}
@@ -0,0 +1,154 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Where the text being parsed came from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Origin {
+    /// The puzzle input of a day.
+    Day(u8),
+    /// Some other file, such as a config file, by what it is.
+    File(&'static str),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Origin::Day(day) => write!(f, "day {}", day),
+            Origin::File(name) => f.write_str(name),
+        }
+    }
+}
+
+/// An error produced by a generator when its puzzle input is malformed.
+///
+/// Lines and columns are 1-based, columns count characters rather than bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub origin: Origin,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub got: String,
+}
+
+impl ParseError {
+    /// An error for input that ended before the generator got everything it needed.
+    pub fn end_of_input(day: u8, input: &str, expected: impl Into<String>) -> ParseError {
+        ParseError {
+            origin: Origin::Day(day),
+            line: input.lines().count() + 1,
+            column: 1,
+            expected: expected.into(),
+            got: String::new(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}, line {}, column {}: expected {}, ",
+            self.origin, self.line, self.column, self.expected
+        )?;
+
+        if self.got.is_empty() {
+            write!(f, "got nothing")
+        } else {
+            write!(f, "got `{}`", self.got)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// A single line of puzzle input, along with where it came from.
+#[derive(Copy, Clone, Debug)]
+pub struct Line<'a> {
+    pub origin: Origin,
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Builds an error pointing at `token`, which should be a slice of this line's text.
+    pub fn error(&self, token: &str, expected: impl Into<String>) -> ParseError {
+        ParseError {
+            origin: self.origin,
+            line: self.number,
+            column: self.column_of(token),
+            expected: expected.into(),
+            got: token.to_string(),
+        }
+    }
+
+    /// Builds an error pointing at the character starting at byte `index`.
+    pub fn char_error(&self, index: usize, expected: impl Into<String>) -> ParseError {
+        let len = self.text[index..].chars().next().map_or(0, char::len_utf8);
+        self.error(&self.text[index..index + len], expected)
+    }
+
+    /// Builds an error pointing just past the end of this line.
+    pub fn end(&self, expected: impl Into<String>) -> ParseError {
+        ParseError {
+            origin: self.origin,
+            line: self.number,
+            column: self.text.chars().count() + 1,
+            expected: expected.into(),
+            got: String::new(),
+        }
+    }
+
+    /// Unwraps a token that may be missing, e.g. the result of `split(..).next()`.
+    pub fn take(&self, token: Option<&'a str>, expected: &str) -> Result<&'a str, ParseError> {
+        token.ok_or_else(|| self.end(expected))
+    }
+
+    pub fn parse<T: FromStr>(&self, token: &str, expected: &str) -> Result<T, ParseError> {
+        token.parse().map_err(|_| self.error(token, expected))
+    }
+
+    fn column_of(&self, token: &str) -> usize {
+        let offset = (token.as_ptr() as usize).wrapping_sub(self.text.as_ptr() as usize);
+
+        if offset <= self.text.len() && self.text.is_char_boundary(offset) {
+            self.text[..offset].chars().count() + 1
+        } else {
+            1
+        }
+    }
+}
+
+/// Iterates over the lines of `input`, numbering them for error reporting.
+pub fn lines(day: u8, input: &str) -> impl Iterator<Item = Line<'_>> {
+    lines_from(Origin::Day(day), input)
+}
+
+/// Like [`lines`], for input that isn't a day's puzzle input.
+pub fn lines_from(origin: Origin, input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(move |(i, text)| Line {
+        origin,
+        number: i + 1,
+        text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_position() {
+        let line = lines(8, "nop +0\njmq +4").nth(1).unwrap();
+        let err = line.error(&line.text[..3], "`acc|jmp|nop`");
+
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(
+            err.to_string(),
+            "day 8, line 2, column 1: expected `acc|jmp|nop`, got `jmq`"
+        );
+        assert_eq!(line.error(&line.text[4..], "").column, 5);
+        assert_eq!(line.end("").column, 7);
+    }
+}
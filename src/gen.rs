@@ -0,0 +1,1097 @@
+//! Synthetic puzzle inputs, for stress-testing and benchmarking the solvers on
+//! inputs larger than the real ones. Every generator is deterministic in its seed.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::day1::Report;
+use crate::day11;
+use crate::day20::{Pattern, SEA_MONSTER};
+use crate::day22::{self, Deck, Rules};
+use crate::day25;
+use crate::grammar::{Rule, RuleId};
+use crate::grid::{Grid, Orientation, Pos};
+use crate::hex::HexDir;
+use crate::number;
+
+/// A small, fast, seedable generator (SplitMix64). Not suitable for anything
+/// but making up puzzle inputs.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, which must not be empty.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// A number in `lo..=hi`.
+    pub fn between(&mut self, lo: u64, hi: u64) -> u64 {
+        lo + self.below(hi - lo + 1)
+    }
+
+    /// True with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i as u64 + 1) as usize);
+        }
+    }
+}
+
+/// Knobs for the generators. Not every day uses every knob.
+#[derive(Clone, Debug)]
+pub struct Params {
+    pub seed: u64,
+    /// How many records to generate: lines, passports, groups, bags, grid rows, tickets,
+    /// tiles...
+    pub size: usize,
+    /// Grid columns for days 3 and 11, fields per ticket for day 16, the side of the
+    /// starting slice for day 17, and allergens for day 21.
+    pub width: usize,
+    /// How many layers of bags inside bags for day 7, of parentheses for day 18, and of rules
+    /// below rules 42 and 31 for day 19.
+    pub depth: usize,
+    /// The fraction of records that are valid, for days 2, 4, 16 and 19.
+    pub valid_rate: f64,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params {
+            seed: 2020,
+            size: 100,
+            width: 31,
+            depth: 6,
+            valid_rate: 0.5,
+        }
+    }
+}
+
+/// Generates an input for `day`, or `None` if there is no generator for it.
+pub fn generate(day: u8, params: &Params) -> Option<String> {
+    let rng = &mut Rng::new(params.seed);
+
+    Some(match day {
+        1 => expense_report(rng, params),
+        2 => passwords(rng, params),
+        3 => grid(rng, params, &[('.', 4), ('#', 1)]),
+        4 => passports(rng, params),
+        5 => boarding_passes(rng, params),
+        6 => answers(rng, params),
+        7 => bags(rng, params),
+        8 => boot_code(rng, params),
+        9 => xmas(rng, params),
+        10 => adapters(rng, params),
+        11 => seats(rng, params),
+        12 => navigation(rng, params),
+        13 => bus_notes(rng, params),
+        14 => docking(rng, params),
+        15 => starting_numbers(rng, params),
+        16 => tickets(rng, params),
+        17 => {
+            let square = Params {
+                size: params.width,
+                ..params.clone()
+            };
+            grid(rng, &square, &[('.', 1), ('#', 1)])
+        }
+        18 => homework(rng, params),
+        19 => monster_messages(rng, params),
+        20 => jigsaw(rng, params),
+        21 => foods(rng, params),
+        22 => decks(rng, params),
+        23 => cups(rng),
+        24 => tile_paths(rng, params),
+        25 => public_keys(rng),
+        _ => return None,
+    })
+}
+
+fn lines(items: impl IntoIterator<Item = String>) -> String {
+    let mut out = String::new();
+    for item in items {
+        out.push_str(&item);
+        out.push('\n');
+    }
+    out
+}
+
+// Exactly one pair and one triple sum to 2020, so both parts have a single
+// answer. Noise is all above 1010, so no pair or triple of it sums to 2020, and
+// any of it that would make a pair or triple with the planted entries is left
+// out.
+fn expense_report(rng: &mut Rng, params: &Params) -> String {
+    let planted = loop {
+        let a = rng.between(1, 1009);
+        let (b, c) = (rng.between(1, 672), rng.between(1, 672));
+        let planted = [a, 2020 - a, b, c, 2020 - b - c];
+        if Report::new(&planted, 2, 2020).count() == 1
+            && Report::new(&planted, 3, 2020).count() == 1
+        {
+            break planted;
+        }
+    };
+
+    let mut taken: HashSet<u64> = planted.iter().map(|x| 2020 - x).collect();
+    for (i, x) in planted.iter().enumerate() {
+        taken.extend(
+            planted[i + 1..]
+                .iter()
+                .filter_map(|y| 2020u64.checked_sub(x + y)),
+        );
+    }
+    taken.extend(&planted);
+
+    let mut entries = planted.to_vec();
+    while entries.len() < params.size.max(5) {
+        let noise = rng.between(1011, 2019);
+        if !taken.contains(&noise) {
+            entries.push(noise);
+        }
+    }
+
+    debug_assert!((2..=3).all(|k| Report::new(&entries, k, 2020).unique().is_some()));
+
+    rng.shuffle(&mut entries);
+    lines(entries.iter().map(u64::to_string))
+}
+
+fn passwords(rng: &mut Rng, params: &Params) -> String {
+    lines((0..params.size).map(|_| {
+        let lower = rng.between(1, 8) as usize;
+        let upper = rng.between(lower as u64 + 1, 16) as usize;
+        let len = rng.between(upper as u64, upper as u64 + 8) as usize;
+        let letter = (b'a' + rng.below(5) as u8) as char;
+
+        let count = if rng.chance(params.valid_rate) {
+            rng.between(lower as u64, upper as u64) as usize
+        } else if rng.chance(0.5) {
+            rng.below(lower as u64) as usize
+        } else if len > upper {
+            rng.between(upper as u64 + 1, len as u64) as usize
+        } else {
+            0
+        };
+
+        let mut password: Vec<char> = (0..len)
+            .map(|i| {
+                if i < count {
+                    return letter;
+                }
+                loop {
+                    let c = (b'a' + rng.below(26) as u8) as char;
+                    if c != letter {
+                        return c;
+                    }
+                }
+            })
+            .collect();
+        rng.shuffle(&mut password);
+
+        format!(
+            "{}-{} {}: {}",
+            lower,
+            upper,
+            letter,
+            password.into_iter().collect::<String>()
+        )
+    }))
+}
+
+// Picks each cell by weight from `cells`.
+fn grid(rng: &mut Rng, params: &Params, cells: &[(char, u64)]) -> String {
+    let total: u64 = cells.iter().map(|x| x.1).sum();
+
+    lines((0..params.size).map(|_| {
+        (0..params.width.max(1))
+            .map(|_| {
+                let mut pick = rng.below(total);
+                for &(c, weight) in cells {
+                    if pick < weight {
+                        return c;
+                    }
+                    pick -= weight;
+                }
+                unreachable!()
+            })
+            .collect()
+    }))
+}
+
+// Some layouts flip back and forth forever instead of settling, so those are
+// rolled again.
+fn seats(rng: &mut Rng, params: &Params) -> String {
+    loop {
+        let layout = grid(rng, params, &[('L', 4), ('.', 1)]);
+        if day11::settles(&day11::parse(&layout).unwrap()) {
+            return layout;
+        }
+    }
+}
+
+static EYE_COLORS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+fn passports(rng: &mut Rng, params: &Params) -> String {
+    let mut out = String::new();
+
+    for _ in 0..params.size {
+        let mut fields = vec![
+            format!("byr:{}", rng.between(1920, 2002)),
+            format!("iyr:{}", rng.between(2010, 2020)),
+            format!("eyr:{}", rng.between(2020, 2030)),
+            if rng.chance(0.5) {
+                format!("hgt:{}cm", rng.between(150, 193))
+            } else {
+                format!("hgt:{}in", rng.between(59, 76))
+            },
+            format!("hcl:#{:06x}", rng.below(1 << 24)),
+            format!("ecl:{}", rng.choose(&EYE_COLORS)),
+            format!("pid:{:09}", rng.below(1_000_000_000)),
+        ];
+
+        if !rng.chance(params.valid_rate) {
+            let i = rng.below(fields.len() as u64) as usize;
+            if rng.chance(0.5) {
+                // invalid for both parts
+                fields.remove(i);
+            } else {
+                // present, but invalid for part 2
+                let key = fields[i][..4].to_string();
+                let value = match &key[..3] {
+                    "byr" => rng.between(2003, 2020).to_string(),
+                    "iyr" => rng.between(2000, 2009).to_string(),
+                    "eyr" => rng.between(2031, 2040).to_string(),
+                    "hgt" => rng.choose(&["200cm", "50in", "170", "6ft"]).to_string(),
+                    "hcl" => format!("{:06x}", rng.below(1 << 24)),
+                    "ecl" => rng.choose(&["xyz", "red", "gold"]).to_string(),
+                    _ => format!("{:08}", rng.below(100_000_000)),
+                };
+                fields[i] = key + &value;
+            }
+        }
+        if rng.chance(0.5) {
+            fields.push(format!("cid:{}", rng.between(1, 350)));
+        }
+
+        rng.shuffle(&mut fields);
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                out.push(if rng.chance(0.25) { '\n' } else { ' ' });
+            }
+            out.push_str(field);
+        }
+        out.push_str("\n\n");
+    }
+
+    out.pop();
+    out
+}
+
+// A run of consecutive seat IDs with exactly one missing from the middle.
+fn boarding_passes(rng: &mut Rng, params: &Params) -> String {
+    let size = params.size.clamp(2, 1022) as u64;
+    let start = rng.between(0, 1023 - size);
+    let missing = start + rng.between(1, size - 1);
+
+    let mut ids: Vec<u64> = (start..=start + size).filter(|&x| x != missing).collect();
+    rng.shuffle(&mut ids);
+
+    lines(ids.into_iter().map(|id| {
+        (0..10)
+            .map(|i| {
+                let bit = (id >> (9 - i)) & 1 == 1;
+                match (i < 7, bit) {
+                    (true, true) => 'B',
+                    (true, false) => 'F',
+                    (false, true) => 'R',
+                    (false, false) => 'L',
+                }
+            })
+            .collect()
+    }))
+}
+
+fn answers(rng: &mut Rng, params: &Params) -> String {
+    let groups: Vec<String> = (0..params.size)
+        .map(|_| {
+            let people = rng.between(1, 5);
+            let mut group = String::new();
+            for _ in 0..people {
+                let mut questions: Vec<char> = ('a'..='z').collect();
+                rng.shuffle(&mut questions);
+                let count = rng.between(1, 26) as usize;
+                group.extend(&questions[..count]);
+                group.push('\n');
+            }
+            group
+        })
+        .collect();
+
+    groups.join("\n")
+}
+
+static ADJECTIVES: [&str; 24] = [
+    "light", "dark", "bright", "muted", "faded", "dotted", "vibrant", "pale", "posh", "wavy",
+    "drab", "dull", "striped", "mirrored", "clear", "dim", "plaid", "shiny", "dashed", "vivid",
+    "mute", "cool", "warm", "soft",
+];
+
+static COLORS: [&str; 24] = [
+    "red", "orange", "white", "yellow", "gold", "olive", "plum", "blue", "black", "tan", "teal",
+    "cyan", "coral", "bronze", "tomato", "silver", "violet", "lime", "maroon", "indigo", "beige",
+    "salmon", "aqua", "green",
+];
+
+// A DAG of bags in `depth + 1` layers, where bags only contain bags from deeper
+// layers and shiny gold sits in the middle.
+fn bags(rng: &mut Rng, params: &Params) -> String {
+    let layers = params.depth + 1;
+    let size = params.size.max(layers);
+
+    let mut names = HashSet::new();
+    let mut ordered = Vec::new();
+    while ordered.len() < size {
+        let adjective = rng.choose(&ADJECTIVES);
+        let color = rng.choose(&COLORS);
+        let name = if names.len() < ADJECTIVES.len() * COLORS.len() / 2 {
+            format!("{} {}", adjective, color)
+        } else {
+            format!("{} {}{}", adjective, color, names.len())
+        };
+        if name != "shiny gold" && names.insert(name.clone()) {
+            ordered.push(name);
+        }
+    }
+
+    let layer_of = |i: usize| i * layers / size;
+    let gold = (0..size).find(|&i| layer_of(i) == layers / 2).unwrap();
+    ordered[gold] = "shiny gold".to_string();
+
+    let mut rules = Vec::new();
+    for (i, name) in ordered.iter().enumerate() {
+        let deeper = match (0..size).find(|&j| layer_of(j) > layer_of(i)) {
+            Some(j) => j,
+            None => {
+                rules.push(format!("{} bags contain no other bags.", name));
+                continue;
+            }
+        };
+
+        let mut inner: Vec<usize> = (0..rng.between(1, 4))
+            .map(|_| rng.between(deeper as u64, size as u64 - 1) as usize)
+            .collect();
+        if i + 1 == gold || (layer_of(i) + 1 == layer_of(gold) && rng.chance(0.2)) {
+            inner.push(gold);
+        }
+        inner.sort_unstable();
+        inner.dedup();
+
+        let inner: Vec<String> = inner
+            .into_iter()
+            .map(|j| {
+                let count = rng.between(1, 5);
+                let plural = if count == 1 { "bag" } else { "bags" };
+                format!("{} {} {}", count, ordered[j], plural)
+            })
+            .collect();
+        rules.push(format!("{} bags contain {}.", name, inner.join(", ")));
+    }
+
+    rng.shuffle(&mut rules);
+    lines(rules)
+}
+
+// Runs straight through except for a single backwards `jmp`, which is the
+// only instruction whose flip makes the program terminate. Forward jumps
+// before it never skip past it, so every other flip still reaches it.
+fn boot_code(rng: &mut Rng, params: &Params) -> String {
+    let size = params.size.max(2) as i64;
+    let looping = rng.between(size as u64 / 2, size as u64 - 1) as i64;
+
+    lines((0..size).map(|i| {
+        if i == looping {
+            return format!("jmp -{}", rng.between(1, i.min(20) as u64));
+        }
+
+        let end = if i < looping { looping } else { size };
+        match rng.below(3) {
+            0 => format!("acc {:+}", rng.between(0, 100) as i64 - 50),
+            1 if i < end - 1 => format!("jmp +{}", rng.between(2, (end - i).min(10) as u64)),
+            _ => format!("nop {:+}", -(rng.between(0, i.min(20) as u64) as i64)),
+        }
+    }))
+}
+
+// Every number is the sum of two of the smallest recent ones, except for one
+// planted number which is the sum of a contiguous run from long before it.
+fn xmas(rng: &mut Rng, params: &Params) -> String {
+    const PREAMBLE: usize = 25;
+    // beyond this the sums no longer fit in a u64
+    let size = params.size.clamp(PREAMBLE + 9, 1200);
+
+    let mut numbers: Vec<u64> = Vec::new();
+    let mut seen = HashSet::new();
+    while numbers.len() < PREAMBLE {
+        let x = rng.between(1, 60);
+        if seen.insert(x) {
+            numbers.push(x);
+        }
+    }
+
+    // keeps going past `size` if the window was still too small to plant in
+    let mut stalls = 0;
+    let earliest = (size - size / 4).max(PREAMBLE + 8);
+    let mut invalid_at = Some(rng.between(earliest as u64, size as u64 - 1) as usize);
+    while numbers.len() < size || invalid_at.is_some() {
+        let window = &numbers[numbers.len() - PREAMBLE..];
+
+        if invalid_at.is_some_and(|i| numbers.len() >= i) {
+            let run = rng.between(2, 4) as usize;
+            let start = rng.below((numbers.len() - PREAMBLE - run) as u64) as usize;
+            let sum: u64 = numbers[start..start + run].iter().sum();
+            if sum < *window.iter().min().unwrap() && seen.insert(sum) {
+                numbers.push(sum);
+                invalid_at = None;
+                continue;
+            }
+        }
+
+        // widen the pool whenever every sum of the smallest ones was already used
+        let mut smallest = window.to_vec();
+        smallest.sort_unstable();
+        let pool = (8 + stalls).min(PREAMBLE) as u64;
+        let a = smallest[rng.below(pool) as usize];
+        let b = smallest[rng.below(pool) as usize];
+        let next = a + b;
+
+        if a != b && seen.insert(next) {
+            numbers.push(next);
+            stalls = 0;
+        } else {
+            stalls += 1;
+        }
+    }
+
+    lines(numbers.iter().map(u64::to_string))
+}
+
+// Gaps of 1 and 3 jolts, with runs of 1s short enough and the arrangement
+// count tracked so that part 2 never overflows.
+fn adapters(rng: &mut Rng, params: &Params) -> String {
+    let mut joltage = 0;
+    let mut adapters = Vec::new();
+    let mut ways = [0u64, 0, 1]; // arrangements reaching two below, one below and at `joltage`
+    let mut run = 0;
+
+    while adapters.len() < params.size {
+        let step = if run < 4 && ways[2] < 1 << 58 && rng.chance(0.7) {
+            1
+        } else {
+            3
+        };
+
+        if step == 1 {
+            run += 1;
+            ways = [ways[1], ways[2], ways.iter().sum()];
+        } else {
+            run = 0;
+            ways = [0, 0, ways[2]];
+        }
+
+        joltage += step;
+        adapters.push(joltage);
+    }
+
+    rng.shuffle(&mut adapters);
+    lines(adapters.iter().map(u64::to_string))
+}
+
+fn navigation(rng: &mut Rng, params: &Params) -> String {
+    lines((0..params.size).map(|_| match rng.below(7) {
+        0 => format!("L{}", 90 * rng.between(1, 3)),
+        1 => format!("R{}", 90 * rng.between(1, 3)),
+        _ => format!(
+            "{}{}",
+            rng.choose(&['N', 'E', 'S', 'W', 'F']),
+            rng.between(1, 100)
+        ),
+    }))
+}
+
+// Distinct prime IDs whose product stays well inside an `i64`, like the real input.
+fn bus_notes(rng: &mut Rng, params: &Params) -> String {
+    let mut primes: Vec<u64> = (13..1000)
+        .filter(|&n| (2..n).take_while(|k| k * k <= n).all(|k| n % k != 0))
+        .collect();
+    rng.shuffle(&mut primes);
+
+    let mut product: u64 = 1;
+    let mut busses = Vec::new();
+    for prime in primes.into_iter().take(params.size.max(1)) {
+        if product * prime > 100_000_000_000_000 {
+            break;
+        }
+        product *= prime;
+
+        if !busses.is_empty() {
+            for _ in 0..rng.below(10) {
+                busses.push("x".to_string());
+            }
+        }
+        busses.push(prime.to_string());
+    }
+
+    format!(
+        "{}\n{}\n",
+        rng.between(100_000, 1_000_000),
+        busses.join(",")
+    )
+}
+
+// At most 9 floating bits per mask, so part 2 writes at most 512 addresses per `mem`.
+fn docking(rng: &mut Rng, params: &Params) -> String {
+    let mut out = Vec::new();
+
+    for _ in 0..params.size {
+        let mut mask: Vec<char> = (0..36)
+            .map(|_| if rng.chance(0.5) { '1' } else { '0' })
+            .collect();
+        for _ in 0..rng.between(0, 9) {
+            let i = rng.below(36) as usize;
+            mask[i] = 'X';
+        }
+        out.push(format!("mask = {}", mask.into_iter().collect::<String>()));
+
+        for _ in 0..rng.between(1, 6) {
+            out.push(format!(
+                "mem[{}] = {}",
+                rng.below(1 << 16),
+                rng.below(1 << 36)
+            ));
+        }
+    }
+
+    lines(out)
+}
+
+fn starting_numbers(rng: &mut Rng, params: &Params) -> String {
+    let size = params.size.max(1) as u64;
+    let mut numbers: Vec<u64> = (0..size.max(20) * 2).collect();
+    rng.shuffle(&mut numbers);
+
+    let numbers: Vec<String> = numbers[..size as usize]
+        .iter()
+        .map(u64::to_string)
+        .collect();
+    numbers.join(",") + "\n"
+}
+
+static FIELDS: [&str; 20] = [
+    "departure location",
+    "departure station",
+    "departure platform",
+    "departure track",
+    "departure date",
+    "departure time",
+    "arrival location",
+    "arrival station",
+    "arrival platform",
+    "arrival track",
+    "class",
+    "duration",
+    "price",
+    "route",
+    "row",
+    "seat",
+    "train",
+    "type",
+    "wagon",
+    "zone",
+];
+
+// Each field rejects only its own small gap of values, so a field can be ruled
+// out of a position by putting its gap value there. Fields are ruled out in a
+// staircase, which leaves exactly one assignment of fields to positions.
+fn tickets(rng: &mut Rng, params: &Params) -> String {
+    let fields = params.width.clamp(6, FIELDS.len());
+    let gap = |field: usize| 100 + 10 * field as u64;
+    let top = gap(fields) + 100;
+    let neutral = |rng: &mut Rng| {
+        if rng.chance(0.5) {
+            rng.between(1, 99)
+        } else {
+            rng.between(gap(fields), top)
+        }
+    };
+
+    let mut out = String::new();
+    for (field, name) in FIELDS.iter().enumerate().take(fields) {
+        writeln!(
+            out,
+            "{}: 1-{} or {}-{}",
+            name,
+            gap(field) - 1,
+            gap(field) + 5,
+            top
+        )
+        .unwrap();
+    }
+
+    let mut position: Vec<usize> = (0..fields).collect();
+    rng.shuffle(&mut position);
+    let mut order: Vec<usize> = (0..fields).collect();
+    rng.shuffle(&mut order);
+
+    let valid_count = ((params.size as f64 * params.valid_rate) as usize).max(fields);
+    let mut valid: Vec<Vec<u64>> = (0..valid_count)
+        .map(|_| (0..fields).map(|_| neutral(rng)).collect())
+        .collect();
+
+    for (o, &field) in order.iter().enumerate() {
+        let mut chosen: Vec<usize> = (0..valid_count).collect();
+        rng.shuffle(&mut chosen);
+        for (&earlier, &ticket) in order[..o].iter().zip(&chosen) {
+            valid[ticket][position[field]] = gap(earlier) + rng.below(5);
+        }
+    }
+
+    let mut nearby = valid;
+    while nearby.len() < params.size {
+        let mut ticket: Vec<u64> = (0..fields).map(|_| neutral(rng)).collect();
+        ticket[rng.below(fields as u64) as usize] = rng.between(top + 1, top + 100);
+        nearby.push(ticket);
+    }
+    rng.shuffle(&mut nearby);
+
+    let join = |ticket: &[u64]| {
+        let values: Vec<String> = ticket.iter().map(u64::to_string).collect();
+        values.join(",")
+    };
+
+    let mine: Vec<u64> = (0..fields).map(|_| rng.between(50, 99)).collect();
+    writeln!(out, "\nyour ticket:\n{}\n\nnearby tickets:", join(&mine)).unwrap();
+    for ticket in &nearby {
+        writeln!(out, "{}", join(ticket)).unwrap();
+    }
+
+    out
+}
+
+// Whatever the precedences, an expression is at most the product of its digits plus one,
+// so keeping that small keeps the answers to both parts in range.
+fn homework(rng: &mut Rng, params: &Params) -> String {
+    lines((0..params.size).map(|_| loop {
+        let expr = expression(rng, params.depth);
+        let bound: f64 = expr
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .map(|d| f64::from(d + 1))
+            .product();
+
+        if bound < 1e12 {
+            break expr;
+        }
+    }))
+}
+
+fn expression(rng: &mut Rng, depth: usize) -> String {
+    let mut out = String::new();
+
+    for i in 0..rng.between(2, 5) {
+        if i > 0 {
+            out.push_str(if rng.chance(0.5) { " + " } else { " * " });
+        }
+
+        if depth > 0 && rng.chance(0.25) {
+            write!(out, "({})", expression(rng, depth - 1)).unwrap();
+        } else {
+            write!(out, "{}", rng.between(1, 9)).unwrap();
+        }
+    }
+
+    out
+}
+
+// Rules 42 and 31 both match words of 2^depth letters, built from layers of rules that each
+// combine two rules from the layer below, like in the real inputs.
+fn monster_messages(rng: &mut Rng, params: &Params) -> String {
+    let depth = params.depth.clamp(1, 5);
+    let mut rules = HashMap::new();
+    rules.insert(1, Rule::Char(b'a'));
+    rules.insert(2, Rule::Char(b'b'));
+
+    let mut layer = vec![1, 2];
+    let mut next_id = 100;
+    for level in 0..depth {
+        let ids = if level + 1 == depth {
+            vec![42, 31]
+        } else {
+            (next_id..next_id + 4).collect()
+        };
+        next_id += ids.len();
+
+        for &id in &ids {
+            let alts = (0..2)
+                .map(|_| vec![*rng.choose(&layer), *rng.choose(&layer)])
+                .collect();
+            rules.insert(id, Rule::Alt(alts));
+        }
+        layer = ids;
+    }
+    rules.insert(0, Rule::Alt(vec![vec![8, 11]]));
+    rules.insert(8, Rule::Alt(vec![vec![42]]));
+    rules.insert(11, Rule::Alt(vec![vec![42, 31]]));
+
+    let mut out: Vec<String> = rules
+        .iter()
+        .map(|(id, rule)| match rule {
+            Rule::Char(c) => format!("{}: \"{}\"", id, *c as char),
+            Rule::Alt(alts) => {
+                let alts: Vec<String> = alts
+                    .iter()
+                    .map(|seq| {
+                        let seq: Vec<String> = seq.iter().map(usize::to_string).collect();
+                        seq.join(" ")
+                    })
+                    .collect();
+                format!("{}: {}", id, alts.join(" | "))
+            }
+        })
+        .collect();
+    out.sort_unstable();
+    rng.shuffle(&mut out);
+    out.push(String::new());
+
+    for _ in 0..params.size {
+        let mut message = String::new();
+        if rng.chance(params.valid_rate) {
+            // matches after the rules are made to loop, and before that too if it is 42 42 31
+            let n = rng.between(2, 4);
+            let m = rng.between(1, n - 1);
+            for _ in 0..n {
+                sample(&rules, 42, rng, &mut message);
+            }
+            for _ in 0..m {
+                sample(&rules, 31, rng, &mut message);
+            }
+        } else {
+            for _ in 0..rng.between(2, 5) << depth {
+                message.push(if rng.chance(0.5) { 'a' } else { 'b' });
+            }
+        }
+        out.push(message);
+    }
+
+    lines(out)
+}
+
+fn sample(rules: &HashMap<RuleId, Rule>, id: RuleId, rng: &mut Rng, out: &mut String) {
+    match &rules[&id] {
+        Rule::Char(c) => out.push(*c as char),
+        Rule::Alt(alts) => {
+            for &id in rng.choose(alts) {
+                sample(rules, id, rng, out);
+            }
+        }
+    }
+}
+
+// Tiles cut out of one big random image with a few sea monsters in it, each sharing its
+// borders with its neighbors. The corners between borders alternate like a checkerboard, so
+// that no border is its own mirror image and there are enough of them to make every one
+// different, which means the tiles only fit together one way.
+fn jigsaw(rng: &mut Rng, params: &Params) -> String {
+    let per_side = ((params.size as f64).sqrt() as usize).clamp(1, 10);
+    let side = per_side * 9 + 1;
+    let mut image = Grid::from_fn(side, side, |pos| {
+        if pos.row % 9 == 0 && pos.col % 9 == 0 {
+            (pos.row + pos.col) / 9 % 2 == 1
+        } else {
+            // sparse enough that sea monsters hardly ever show up by chance
+            rng.chance(0.35)
+        }
+    });
+
+    let inner = per_side * 8;
+    let monster = Pattern::new(SEA_MONSTER);
+    if inner >= 20 {
+        for _ in 0..per_side {
+            let at = Pos::new(
+                rng.below(inner as u64 - 2) as usize,
+                rng.below(inner as u64 - 19) as usize,
+            );
+            for pos in monster.cells(at) {
+                image[Pos::new(
+                    pos.row / 8 * 9 + 1 + pos.row % 8,
+                    pos.col / 8 * 9 + 1 + pos.col % 8,
+                )] = true;
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for line in 0..=per_side {
+        for segment in 0..per_side {
+            for &horizontal in &[true, false] {
+                let cells: Vec<Pos> = (0..10)
+                    .map(|i| {
+                        let (along, across) = (segment * 9 + i, line * 9);
+                        if horizontal {
+                            Pos::new(across, along)
+                        } else {
+                            Pos::new(along, across)
+                        }
+                    })
+                    .collect();
+
+                loop {
+                    for &pos in &cells[1..9] {
+                        image[pos] = rng.chance(0.5);
+                    }
+                    let bits = cells
+                        .iter()
+                        .fold(0u32, |acc, &pos| acc << 1 | image[pos] as u32);
+                    if seen.insert(bits.min(bits.reverse_bits() >> 22)) {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut ids = HashSet::new();
+    let mut tiles: Vec<String> = (0..per_side * per_side)
+        .map(|i| {
+            let mut id = rng.between(1000, 9999);
+            while !ids.insert(id) {
+                id = rng.between(1000, 9999);
+            }
+
+            let (top, left) = (i / per_side * 9, i % per_side * 9);
+            let tile = Grid::from_fn(10, 10, |pos| image[Pos::new(top + pos.row, left + pos.col)]);
+            let tile = tile.oriented(*rng.choose(&Orientation::ALL));
+
+            let mut out = format!("Tile {}:", id);
+            for row in 0..10 {
+                out.push('\n');
+                out.extend((0..10).map(|col| if tile[Pos::new(row, col)] { '#' } else { '.' }));
+            }
+            out
+        })
+        .collect();
+    rng.shuffle(&mut tiles);
+
+    tiles.join("\n\n") + "\n"
+}
+
+static ALLERGENS: &[&str] = &[
+    "dairy",
+    "eggs",
+    "fish",
+    "nuts",
+    "peanuts",
+    "sesame",
+    "shellfish",
+    "soy",
+    "wheat",
+];
+
+fn word(rng: &mut Rng) -> String {
+    (0..rng.between(4, 8))
+        .map(|_| (b'a' + rng.below(26) as u8) as char)
+        .collect()
+}
+
+// Each allergen gets a food listing only it, with its own ingredient and those of the
+// allergens before it, so narrowing them down in order leaves exactly one way to match them.
+fn foods(rng: &mut Rng, params: &Params) -> String {
+    let mut allergens = ALLERGENS[..params.width.clamp(1, ALLERGENS.len())].to_vec();
+    rng.shuffle(&mut allergens);
+
+    let mut names = HashSet::new();
+    let mut ingredients = Vec::new();
+    while ingredients.len() < allergens.len() * 5 {
+        let name = word(rng);
+        if names.insert(name.clone()) {
+            ingredients.push(name);
+        }
+    }
+    let (dangerous, safe) = ingredients.split_at(allergens.len());
+
+    let mut foods: Vec<(Vec<&str>, Vec<&str>)> = (0..allergens.len())
+        .map(|k| {
+            let contents = dangerous[..=k].iter().map(String::as_str).collect();
+            (contents, vec![allergens[k]])
+        })
+        .collect();
+
+    while foods.len() < params.size {
+        let mut listed = Vec::new();
+        let mut contents = Vec::new();
+        for (k, &allergen) in allergens.iter().enumerate() {
+            if rng.chance(0.2) {
+                contents.push(dangerous[k].as_str());
+                if rng.chance(0.7) {
+                    listed.push(allergen);
+                }
+            }
+        }
+        for ingredient in safe {
+            if rng.chance(0.3) {
+                contents.push(ingredient);
+            }
+        }
+        if contents.is_empty() {
+            contents.push(rng.choose(safe).as_str());
+        }
+        foods.push((contents, listed));
+    }
+    rng.shuffle(&mut foods);
+
+    lines(foods.into_iter().map(|(mut contents, listed)| {
+        rng.shuffle(&mut contents);
+        let mut food = contents.join(" ");
+        if !listed.is_empty() {
+            write!(food, " (contains {})", listed.join(", ")).unwrap();
+        }
+        food
+    }))
+}
+
+// Some deals make the plain game go round in circles forever, so those are dealt again.
+fn decks(rng: &mut Rng, params: &Params) -> String {
+    let per_player = (params.size / 2).clamp(1, 25) as u32;
+    let mut cards: Vec<u32> = (1..=2 * per_player).collect();
+
+    loop {
+        rng.shuffle(&mut cards);
+        let (one, two) = cards.split_at(per_player as usize);
+        let decks = [Deck::new(one.to_vec()), Deck::new(two.to_vec())];
+        if day22::play(&decks, Rules::Plain, None).is_some() {
+            break;
+        }
+    }
+
+    let deal = |cards: &[u32]| lines(cards.iter().map(u32::to_string));
+    let (one, two) = cards.split_at(per_player as usize);
+    format!("Player 1:\n{}\nPlayer 2:\n{}", deal(one), deal(two))
+}
+
+fn cups(rng: &mut Rng) -> String {
+    let mut labels: Vec<u32> = (1..=9).collect();
+    rng.shuffle(&mut labels);
+    lines(std::iter::once(labels.iter().map(u32::to_string).collect()))
+}
+
+// Some paths lead back to a tile flipped before, so it is flipped back.
+fn tile_paths(rng: &mut Rng, params: &Params) -> String {
+    let mut paths: Vec<String> = Vec::new();
+    while paths.len() < params.size {
+        let path = if !paths.is_empty() && rng.chance(0.1) {
+            // the same tile by another route
+            let detour = if rng.chance(0.5) { "ew" } else { "nwse" };
+            format!("{}{}", rng.choose(&paths), detour)
+        } else {
+            (0..rng.between(5, 20))
+                .map(|_| rng.choose(&HexDir::ALL).name())
+                .collect()
+        };
+        paths.push(path);
+    }
+    lines(paths)
+}
+
+fn public_keys(rng: &mut Rng) -> String {
+    lines((0..2).map(|_| {
+        let loop_size = rng.between(1, day25::MODULUS - 2);
+        number::mod_pow(day25::SUBJECT, loop_size, day25::MODULUS).to_string()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{day1, DAYS};
+
+    #[test]
+    fn test_deterministic() {
+        let params = Params::default();
+        for day in DAYS {
+            assert_eq!(generate(day.day, &params), generate(day.day, &params));
+        }
+    }
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        for seed in 0..3 {
+            let small = Params {
+                seed,
+                size: 60,
+                width: 12,
+                ..Params::default()
+            };
+            let tiny = Params {
+                seed,
+                size: 1,
+                ..Params::default()
+            };
+
+            for params in [
+                small,
+                tiny,
+                Params {
+                    seed,
+                    ..Params::default()
+                },
+            ] {
+                solve_all(&params);
+            }
+        }
+    }
+
+    fn solve_all(params: &Params) {
+        for day in DAYS {
+            let input = generate(day.day, params).unwrap();
+            let parsed = day.parse(&input).unwrap();
+            parsed.part1().unwrap();
+            if day.day != 15 && day.parts == 2 {
+                parsed.part2().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_expense_report_is_unique() {
+        for seed in 0..50 {
+            let params = Params {
+                seed,
+                ..Params::default()
+            };
+            let input = day1::parse(&generate(1, &params).unwrap()).unwrap();
+
+            for k in 2..=3 {
+                let report = Report::new(&input, k, 2020);
+                assert!(report.unique().is_some(), "seed {}: {}", seed, report);
+            }
+        }
+    }
+}
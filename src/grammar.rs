@@ -0,0 +1,232 @@
+//! Grammars of numbered rules, each either a literal character or a choice between
+//! sequences of other rules, and a matcher that copes with recursive rules.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+pub type RuleId = usize;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Rule {
+    Char(u8),
+    /// Matches if any one of the sequences does.
+    Alt(Vec<Vec<RuleId>>),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Grammar {
+    rules: HashMap<RuleId, Rule>,
+}
+
+/// How a rule matched part of a message: which alternative was taken, and how each rule in it
+/// matched in turn.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Derivation {
+    Char(RuleId, u8),
+    Node {
+        rule: RuleId,
+        alt: usize,
+        children: Vec<Derivation>,
+    },
+}
+
+impl Grammar {
+    pub fn new() -> Grammar {
+        Grammar::default()
+    }
+
+    /// Adds a rule, returning the one it replaced.
+    pub fn insert(&mut self, id: RuleId, rule: Rule) -> Option<Rule> {
+        self.rules.insert(id, rule)
+    }
+
+    pub fn get(&self, id: RuleId) -> Option<&Rule> {
+        self.rules.get(&id)
+    }
+
+    /// Whether all of `message` matches `rule`.
+    ///
+    /// Rules may refer to themselves, as long as every path back to a rule consumes at least one
+    /// character first; left-recursive rules only match what they can without the recursion.
+    pub fn matches(&self, rule: RuleId, message: &str) -> bool {
+        let message = message.as_bytes();
+        Matcher::new(self, message)
+            .ends(rule, 0)
+            .contains(&message.len())
+    }
+
+    /// How all of `message` matches `rule`, if it does. When there are several ways,
+    /// earlier alternatives are preferred.
+    pub fn derive(&self, rule: RuleId, message: &str) -> Option<Derivation> {
+        let message = message.as_bytes();
+        Matcher::new(self, message).derive(rule, 0, message.len())
+    }
+}
+
+struct Matcher<'a> {
+    grammar: &'a Grammar,
+    message: &'a [u8],
+    ends: HashMap<(RuleId, usize), Vec<usize>>,
+    in_progress: HashSet<(RuleId, usize)>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(grammar: &'a Grammar, message: &'a [u8]) -> Matcher<'a> {
+        Matcher {
+            grammar,
+            message,
+            ends: HashMap::new(),
+            in_progress: HashSet::new(),
+        }
+    }
+
+    // Every position where a match of `rule` starting at `start` can end.
+    fn ends(&mut self, rule: RuleId, start: usize) -> Vec<usize> {
+        if let Some(ends) = self.ends.get(&(rule, start)) {
+            return ends.clone();
+        }
+        if !self.in_progress.insert((rule, start)) {
+            return Vec::new();
+        }
+
+        let mut ends = Vec::new();
+        match self.grammar.get(rule) {
+            Some(Rule::Char(c)) if self.message.get(start) == Some(c) => ends.push(start + 1),
+            Some(Rule::Alt(alts)) => {
+                for seq in alts {
+                    for end in self.seq_ends(seq, start) {
+                        if !ends.contains(&end) {
+                            ends.push(end);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        self.in_progress.remove(&(rule, start));
+        self.ends.insert((rule, start), ends.clone());
+        ends
+    }
+
+    fn seq_ends(&mut self, seq: &[RuleId], start: usize) -> Vec<usize> {
+        let mut positions = vec![start];
+        for &rule in seq {
+            let mut next = Vec::new();
+            for pos in positions {
+                for end in self.ends(rule, pos) {
+                    if !next.contains(&end) {
+                        next.push(end);
+                    }
+                }
+            }
+            positions = next;
+        }
+        positions
+    }
+
+    fn derive(&mut self, rule: RuleId, start: usize, end: usize) -> Option<Derivation> {
+        if !self.ends(rule, start).contains(&end) {
+            return None;
+        }
+
+        match self.grammar.get(rule)? {
+            Rule::Char(c) => Some(Derivation::Char(rule, *c)),
+            Rule::Alt(alts) => alts.iter().enumerate().find_map(|(alt, seq)| {
+                let children = self.derive_seq(seq, start, end)?;
+                Some(Derivation::Node {
+                    rule,
+                    alt,
+                    children,
+                })
+            }),
+        }
+    }
+
+    fn derive_seq(&mut self, seq: &[RuleId], start: usize, end: usize) -> Option<Vec<Derivation>> {
+        let (&first, rest) = seq.split_first()?;
+        if rest.is_empty() {
+            return Some(vec![self.derive(first, start, end)?]);
+        }
+
+        for mid in self.ends(first, start) {
+            if !self.seq_ends(rest, mid).contains(&end) {
+                continue;
+            }
+            if let Some(mut children) = self.derive_seq(rest, mid, end) {
+                children.insert(0, self.derive(first, start, mid)?);
+                return Some(children);
+            }
+        }
+        None
+    }
+}
+
+impl Derivation {
+    pub fn rule(&self) -> RuleId {
+        match self {
+            Derivation::Char(rule, _) => *rule,
+            Derivation::Node { rule, .. } => *rule,
+        }
+    }
+}
+
+/// Writes the tree as `rule(children...)`, with characters in quotes.
+impl fmt::Display for Derivation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Derivation::Char(rule, c) => write!(f, "{}({:?})", rule, *c as char),
+            Derivation::Node { rule, children, .. } => {
+                write!(f, "{}(", rule)?;
+                for (i, child) in children.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", child)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0: 1 0 2 | 1 2, 1: "a", 2: "b" -- that is, a^n b^n
+    fn balanced() -> Grammar {
+        let mut grammar = Grammar::new();
+        grammar.insert(0, Rule::Alt(vec![vec![1, 0, 2], vec![1, 2]]));
+        grammar.insert(1, Rule::Char(b'a'));
+        grammar.insert(2, Rule::Char(b'b'));
+        grammar
+    }
+
+    #[test]
+    fn test_recursion() {
+        let grammar = balanced();
+        for &(message, expected) in &[
+            ("ab", true),
+            ("aaabbb", true),
+            ("aabbb", false),
+            ("", false),
+        ] {
+            assert_eq!(grammar.matches(0, message), expected, "{}", message);
+        }
+
+        assert_eq!(
+            grammar.derive(0, "aabb").unwrap().to_string(),
+            "0(1('a') 0(1('a') 2('b')) 2('b'))"
+        );
+        assert_eq!(grammar.derive(0, "aab"), None);
+    }
+
+    #[test]
+    fn test_left_recursion_terminates() {
+        let mut grammar = balanced();
+        grammar.insert(3, Rule::Alt(vec![vec![3, 1], vec![1]]));
+        assert!(grammar.matches(3, "a"));
+        assert!(!grammar.matches(3, "b"));
+    }
+}
@@ -0,0 +1,478 @@
+//! A rectangular grid of cells, as used by the days whose input is a character map.
+
+use std::fmt;
+use std::iter;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use crate::error::{self, Line, Origin, ParseError};
+
+/// A position in a [`Grid`], counting rows down from the top and columns right from the left.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    pub const fn new(row: usize, col: usize) -> Pos {
+        Pos { row, col }
+    }
+}
+
+/// A step from one position to another, in rows and columns.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dir {
+    pub rows: isize,
+    pub cols: isize,
+}
+
+impl Dir {
+    pub const UP: Dir = Dir::new(-1, 0);
+    pub const DOWN: Dir = Dir::new(1, 0);
+    pub const LEFT: Dir = Dir::new(0, -1);
+    pub const RIGHT: Dir = Dir::new(0, 1);
+    pub const UP_LEFT: Dir = Dir::new(-1, -1);
+    pub const UP_RIGHT: Dir = Dir::new(-1, 1);
+    pub const DOWN_LEFT: Dir = Dir::new(1, -1);
+    pub const DOWN_RIGHT: Dir = Dir::new(1, 1);
+
+    /// The four directions sharing an edge with a cell.
+    pub const ORTHOGONAL: [Dir; 4] = [Dir::UP, Dir::RIGHT, Dir::DOWN, Dir::LEFT];
+    /// All eight directions, diagonals included.
+    pub const ALL: [Dir; 8] = [
+        Dir::UP_LEFT,
+        Dir::UP,
+        Dir::UP_RIGHT,
+        Dir::LEFT,
+        Dir::RIGHT,
+        Dir::DOWN_LEFT,
+        Dir::DOWN,
+        Dir::DOWN_RIGHT,
+    ];
+
+    pub const fn new(rows: isize, cols: isize) -> Dir {
+        Dir { rows, cols }
+    }
+}
+
+/// Which edges of a grid wrap around to the opposite one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Wrap {
+    None,
+    /// Walking off the left or right edge comes back in on the other side.
+    Horizontal,
+    /// Walking off the top or bottom edge comes back in on the other side.
+    Vertical,
+    Both,
+}
+
+impl Wrap {
+    fn rows(self) -> bool {
+        matches!(self, Wrap::Vertical | Wrap::Both)
+    }
+
+    fn cols(self) -> bool {
+        matches!(self, Wrap::Horizontal | Wrap::Both)
+    }
+}
+
+/// One of the eight ways to turn and flip a rectangle: a mirror image left to right, if
+/// `flipped`, followed by `turns` quarter turns clockwise. Together they form the dihedral group
+/// of order 8, with [`Orientation::then`] as the group operation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    flipped: bool,
+    turns: u8,
+}
+
+impl Orientation {
+    pub const IDENTITY: Orientation = Orientation::new(false, 0);
+
+    pub const ALL: [Orientation; 8] = [
+        Orientation::new(false, 0),
+        Orientation::new(false, 1),
+        Orientation::new(false, 2),
+        Orientation::new(false, 3),
+        Orientation::new(true, 0),
+        Orientation::new(true, 1),
+        Orientation::new(true, 2),
+        Orientation::new(true, 3),
+    ];
+
+    pub const fn new(flipped: bool, turns: u8) -> Orientation {
+        Orientation {
+            flipped,
+            turns: turns % 4,
+        }
+    }
+
+    pub fn flipped(self) -> bool {
+        self.flipped
+    }
+
+    pub fn turns(self) -> u8 {
+        self.turns
+    }
+
+    /// This orientation followed by `other`.
+    pub fn then(self, other: Orientation) -> Orientation {
+        // flipping after a turn is the same as flipping before the opposite turn
+        let turns = if other.flipped {
+            4 - self.turns
+        } else {
+            self.turns
+        };
+        Orientation::new(self.flipped ^ other.flipped, turns + other.turns)
+    }
+
+    pub fn inverse(self) -> Orientation {
+        if self.flipped {
+            self
+        } else {
+            Orientation::new(false, 4 - self.turns)
+        }
+    }
+
+    /// The height and width of a rectangle of the given height and width, once oriented.
+    pub fn dims(self, (height, width): (usize, usize)) -> (usize, usize) {
+        if self.turns.is_multiple_of(2) {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Where `pos` ends up once a rectangle of the given height and width is oriented.
+    pub fn apply(self, pos: Pos, (height, width): (usize, usize)) -> Pos {
+        let Pos { mut row, mut col } = pos;
+        let (mut height, mut width) = (height, width);
+
+        if self.flipped {
+            col = width - 1 - col;
+        }
+        for _ in 0..self.turns {
+            let turned = (col, height - 1 - row);
+            row = turned.0;
+            col = turned.1;
+            std::mem::swap(&mut height, &mut width);
+        }
+
+        Pos::new(row, col)
+    }
+}
+
+/// A cell type that can be read from and written as a single character.
+pub trait Cell: Sized {
+    /// Describes the accepted characters, for error messages.
+    const EXPECTED: &'static str;
+
+    fn from_char(c: char) -> Option<Self>;
+    fn to_char(&self) -> char;
+}
+
+/// A rectangular grid stored row by row in a single `Vec`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+    wrap: Wrap,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+            wrap: Wrap::None,
+        }
+    }
+
+    /// Builds a grid by calling `f` with every position, row by row.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Grid<T> {
+        let mut cells = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for col in 0..width {
+                cells.push(f(Pos::new(row, col)));
+            }
+        }
+
+        Grid {
+            width,
+            height,
+            cells,
+            wrap: Wrap::None,
+        }
+    }
+
+    /// Makes stepping off the given edges of the grid wrap around.
+    pub fn with_wrap(mut self, wrap: Wrap) -> Grid<T> {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(move |i| &mut self.cells[i])
+    }
+
+    /// Iterates over the cells row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Iterates over every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| Pos::new(row, col)))
+    }
+
+    /// The position one step from `pos` in `dir`, or `None` if that leaves the grid.
+    pub fn step(&self, pos: Pos, dir: Dir) -> Option<Pos> {
+        Some(Pos {
+            row: offset(pos.row, dir.rows, self.height, self.wrap.rows())?,
+            col: offset(pos.col, dir.cols, self.width, self.wrap.cols())?,
+        })
+    }
+
+    /// The up to four positions sharing an edge with `pos`.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Dir::ORTHOGONAL
+            .iter()
+            .filter_map(move |&dir| self.step(pos, dir))
+    }
+
+    /// The up to eight positions surrounding `pos`, diagonals included.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Dir::ALL.iter().filter_map(move |&dir| self.step(pos, dir))
+    }
+
+    /// Every position reached by repeatedly stepping from `pos` in `dir`, not including `pos`
+    /// itself, until the grid is left. Never ends if the grid wraps in the direction of travel.
+    pub fn ray(&self, pos: Pos, dir: Dir) -> impl Iterator<Item = Pos> + '_ {
+        iter::successors(self.step(pos, dir), move |&pos| self.step(pos, dir))
+    }
+
+    /// A copy of the grid turned and flipped by `orientation`.
+    pub fn oriented(&self, orientation: Orientation) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let (height, width) = orientation.dims((self.height, self.width));
+        let inverse = orientation.inverse();
+
+        Grid::from_fn(width, height, |pos| {
+            self[inverse.apply(pos, (height, width))].clone()
+        })
+    }
+
+    fn index_of(&self, pos: Pos) -> Option<usize> {
+        if pos.row < self.height && pos.col < self.width {
+            Some(pos.row * self.width + pos.col)
+        } else {
+            None
+        }
+    }
+}
+
+fn offset(i: usize, by: isize, len: usize, wrap: bool) -> Option<usize> {
+    if len == 0 {
+        None
+    } else if wrap {
+        Some((i as isize + by).rem_euclid(len as isize) as usize)
+    } else {
+        i.checked_add_signed(by).filter(|&i| i < len)
+    }
+}
+
+impl<T: Cell> Grid<T> {
+    /// Parses one row per line, attributing errors to `day`.
+    pub fn parse(day: u8, input: &str) -> Result<Grid<T>, ParseError> {
+        Grid::from_lines(error::lines(day, input))
+    }
+
+    /// Parses one row per line, for grids that are only part of an input.
+    pub fn from_lines<'a>(
+        lines: impl IntoIterator<Item = Line<'a>>,
+    ) -> Result<Grid<T>, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for line in lines {
+            let before = cells.len();
+            for (i, c) in line.text.char_indices() {
+                cells.push(T::from_char(c).ok_or_else(|| line.char_error(i, T::EXPECTED))?);
+            }
+
+            let len = cells.len() - before;
+            match width {
+                Some(width) if width != len => {
+                    return Err(line.error(line.text, format!("a row of width {}", width)));
+                }
+                _ => width = Some(len),
+            }
+            height += 1;
+        }
+
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+            wrap: Wrap::None,
+        })
+    }
+}
+
+impl<T: Cell> FromStr for Grid<T> {
+    type Err = ParseError;
+
+    /// Like [`Grid::parse`], but for grids that aren't a day's input.
+    fn from_str(s: &str) -> Result<Grid<T>, ParseError> {
+        Grid::from_lines(error::lines_from(Origin::File("grid"), s))
+    }
+}
+
+impl<T: Cell> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, row) in self.cells.chunks(self.width.max(1)).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell.to_char())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.index_of(pos) {
+            Some(i) => &self.cells[i],
+            None => panic!(
+                "{:?} is outside of a {}x{} grid",
+                pos, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        match self.index_of(pos) {
+            Some(i) => &mut self.cells[i],
+            None => panic!(
+                "{:?} is outside of a {}x{} grid",
+                pos, self.width, self.height
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl Cell for bool {
+        const EXPECTED: &'static str = "`.` or `#`";
+
+        fn from_char(c: char) -> Option<bool> {
+            match c {
+                '.' => Some(false),
+                '#' => Some(true),
+                _ => None,
+            }
+        }
+
+        fn to_char(&self) -> char {
+            if *self {
+                '#'
+            } else {
+                '.'
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid: Grid<bool> = "#..\n.#.".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert!(grid[Pos::new(1, 1)]);
+        assert_eq!(grid.to_string(), "#..\n.#.");
+
+        let err = Grid::<bool>::parse(3, "#..\n.#").unwrap_err();
+        assert_eq!(
+            (err.origin, err.line, err.expected.as_str()),
+            (Origin::Day(3), 2, "a row of width 3")
+        );
+        let err = "#.x".parse::<Grid<bool>>().unwrap_err();
+        assert_eq!((err.column, err.got.as_str()), (3, "x"));
+        assert!(err.to_string().starts_with("grid, line 1, column 3:"));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(3, 3, false);
+        assert_eq!(grid.neighbors4(Pos::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbors8(Pos::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbors8(Pos::new(1, 1)).count(), 8);
+
+        let grid = grid.with_wrap(Wrap::Both);
+        assert_eq!(grid.neighbors8(Pos::new(0, 0)).count(), 8);
+        assert_eq!(
+            grid.step(Pos::new(0, 0), Dir::UP_LEFT),
+            Some(Pos::new(2, 2))
+        );
+    }
+
+    #[test]
+    fn test_orientations() {
+        let grid: Grid<bool> = "#..\n.##".parse().unwrap();
+        assert_eq!(
+            grid.oriented(Orientation::new(false, 1)).to_string(),
+            ".#\n#.\n#."
+        );
+        assert_eq!(
+            grid.oriented(Orientation::new(true, 0)).to_string(),
+            "..#\n##."
+        );
+
+        for &a in &Orientation::ALL {
+            assert_eq!(grid.oriented(a).oriented(a.inverse()), grid);
+            for &b in &Orientation::ALL {
+                assert_eq!(grid.oriented(a).oriented(b), grid.oriented(a.then(b)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = Grid::new(4, 3, false);
+        let ray: Vec<Pos> = grid.ray(Pos::new(0, 0), Dir::DOWN_RIGHT).collect();
+        assert_eq!(ray, [Pos::new(1, 1), Pos::new(2, 2)]);
+
+        let grid = grid.with_wrap(Wrap::Horizontal);
+        let ray: Vec<Pos> = grid.ray(Pos::new(0, 2), Dir::new(1, 3)).collect();
+        assert_eq!(ray, [Pos::new(1, 1), Pos::new(2, 0)]);
+    }
+}
@@ -0,0 +1,173 @@
+//! Hexagonal grids with rows running east to west, in axial coordinates.
+
+use std::fmt;
+use std::ops::Add;
+
+use crate::error::{Line, ParseError};
+
+/// A hexagon, `q` steps east and `r` steps south-east of the origin. As cube coordinates these
+/// are `x = q`, `z = r` and `y = -q - r`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex::new(0, 0);
+
+    pub const fn new(q: i32, r: i32) -> Hex {
+        Hex { q, r }
+    }
+
+    /// The hexagon at cube coordinates `(x, y, z)`, which must add up to zero.
+    pub fn from_cube(x: i32, y: i32, z: i32) -> Option<Hex> {
+        (x + y + z == 0).then_some(Hex::new(x, z))
+    }
+
+    pub fn cube(self) -> (i32, i32, i32) {
+        (self.q, -self.q - self.r, self.r)
+    }
+
+    pub fn step(self, dir: HexDir) -> Hex {
+        let (dq, dr) = dir.offset();
+        Hex::new(self.q + dq, self.r + dr)
+    }
+
+    pub fn neighbors(self) -> impl Iterator<Item = Hex> {
+        HexDir::ALL.iter().map(move |&dir| self.step(dir))
+    }
+
+    /// The fewest steps it takes to get to `other`.
+    pub fn distance(self, other: Hex) -> u32 {
+        let (x1, y1, z1) = self.cube();
+        let (x2, y2, z2) = other.cube();
+        (x1 - x2)
+            .unsigned_abs()
+            .max((y1 - y2).unsigned_abs())
+            .max((z1 - z2).unsigned_abs())
+    }
+}
+
+impl Add<HexDir> for Hex {
+    type Output = Hex;
+
+    fn add(self, dir: HexDir) -> Hex {
+        self.step(dir)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HexDir {
+    E,
+    SE,
+    SW,
+    W,
+    NW,
+    NE,
+}
+
+impl HexDir {
+    /// Clockwise, starting from east.
+    pub const ALL: [HexDir; 6] = [
+        HexDir::E,
+        HexDir::SE,
+        HexDir::SW,
+        HexDir::W,
+        HexDir::NW,
+        HexDir::NE,
+    ];
+
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            HexDir::E => (1, 0),
+            HexDir::SE => (0, 1),
+            HexDir::SW => (-1, 1),
+            HexDir::W => (-1, 0),
+            HexDir::NW => (0, -1),
+            HexDir::NE => (1, -1),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HexDir::E => "e",
+            HexDir::SE => "se",
+            HexDir::SW => "sw",
+            HexDir::W => "w",
+            HexDir::NW => "nw",
+            HexDir::NE => "ne",
+        }
+    }
+}
+
+impl fmt::Display for HexDir {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses a line of directions written one after another with no separators, like `esenee`.
+pub fn parse_steps(line: &Line) -> Result<Vec<HexDir>, ParseError> {
+    let mut steps = Vec::new();
+    let mut rest = line.text;
+
+    while !rest.is_empty() {
+        let dir = HexDir::ALL
+            .iter()
+            .find(|dir| rest.starts_with(dir.name()))
+            .ok_or_else(|| {
+                line.char_error(
+                    line.text.len() - rest.len(),
+                    "`e`, `se`, `sw`, `w`, `nw` or `ne`",
+                )
+            })?;
+
+        steps.push(*dir);
+        rest = &rest[dir.name().len()..];
+    }
+
+    Ok(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error;
+
+    fn walk(path: &str) -> Hex {
+        let line = error::lines(0, path).next().unwrap();
+        parse_steps(&line)
+            .unwrap()
+            .into_iter()
+            .fold(Hex::ORIGIN, Hex::add)
+    }
+
+    #[test]
+    fn test_steps() {
+        assert_eq!(walk("esew"), Hex::ORIGIN.step(HexDir::SE));
+        assert_eq!(walk("nwwswee"), Hex::ORIGIN);
+        assert_eq!(walk("nenenesw").distance(Hex::ORIGIN), 2);
+
+        let line = error::lines(0, "nwsx").next().unwrap();
+        assert_eq!(parse_steps(&line).unwrap_err().column, 3);
+    }
+
+    #[test]
+    fn test_coordinates() {
+        for dir in &HexDir::ALL {
+            let hex = Hex::ORIGIN.step(*dir);
+            let (x, y, z) = hex.cube();
+            assert_eq!(Hex::from_cube(x, y, z), Some(hex));
+            assert_eq!(hex.distance(Hex::ORIGIN), 1);
+        }
+        assert_eq!(Hex::from_cube(1, 1, 1), None);
+
+        let hex = Hex::new(3, -5);
+        let mut neighbors: Vec<Hex> = hex.neighbors().collect();
+        neighbors.sort();
+        neighbors.dedup();
+        assert_eq!(neighbors.len(), 6);
+        assert!(neighbors.iter().all(|n| n.distance(hex) == 1));
+    }
+}
@@ -0,0 +1,98 @@
+//! Modular arithmetic on `u64`s, with products worked out in `u128` so that they can't overflow.
+
+use std::collections::HashMap;
+
+pub fn mod_mul(a: u64, b: u64, modulus: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(modulus)) as u64
+}
+
+/// `base` to the power of `exp`, by repeated squaring.
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut base = base % modulus;
+    let mut result = 1 % modulus;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mod_mul(result, base, modulus);
+        }
+        base = mod_mul(base, base, modulus);
+        exp >>= 1;
+    }
+
+    result
+}
+
+/// The smallest `x` such that `base` to the power of `x` is `target`, if there is one, for a
+/// prime modulus. Uses baby-step giant-step, which takes time and space in the order of the
+/// square root of the modulus.
+pub fn discrete_log(base: u64, target: u64, modulus: u64) -> Option<u64> {
+    let (base, target) = (base % modulus, target % modulus);
+    if modulus == 1 {
+        return Some(0);
+    }
+    if base == 0 {
+        return match target {
+            1 => Some(0),
+            0 => Some(1),
+            _ => None,
+        };
+    }
+
+    let m = (modulus as f64).sqrt().ceil() as u64;
+
+    // the first `j` at which each of `base^j` comes up
+    let mut baby_steps = HashMap::new();
+    let mut power = 1 % modulus;
+    for j in 0..m {
+        baby_steps.entry(power).or_insert(j);
+        power = mod_mul(power, base, modulus);
+    }
+
+    // dividing by `base^m`, by Fermat's little theorem
+    let giant_step = mod_pow(mod_pow(base, m, modulus), modulus - 2, modulus);
+    let mut value = target;
+    for i in 0..m {
+        if let Some(&j) = baby_steps.get(&value) {
+            return Some(i * m + j);
+        }
+        value = mod_mul(value, giant_step, modulus);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(7, 8, 20201227), 5764801);
+        assert_eq!(mod_pow(3, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX - 1, 3, u64::MAX), u64::MAX - 1);
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(discrete_log(7, 5764801, 20201227), Some(8));
+        assert_eq!(discrete_log(7, 17807724, 20201227), Some(11));
+        assert_eq!(discrete_log(7, 1, 20201227), Some(0));
+        assert_eq!(discrete_log(3, 5, 1), Some(0));
+
+        // big enough that `(modulus - 2) * m` doesn't fit in a u64
+        let p = (1 << 44) - 17;
+        assert_eq!(
+            discrete_log(3, mod_pow(3, 1_000_003, p), p),
+            Some(1_000_003)
+        );
+
+        // 2 only generates the squares mod 7
+        assert_eq!(discrete_log(2, 4, 7), Some(2));
+        assert_eq!(discrete_log(2, 3, 7), None);
+
+        // 2 generates everything mod 101, so every power has a single log below 100
+        for x in 0..100 {
+            assert_eq!(discrete_log(2, mod_pow(2, x, 101), 101), Some(x));
+        }
+    }
+}
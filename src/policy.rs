@@ -0,0 +1,666 @@
+//! Rules for which entries of the day 2 password database are valid, and a small language for
+//! writing them down in a config file.
+//!
+//! A config file has a policy per line, as a name and the rules it is made of, all of which have
+//! to hold:
+//!
+//! ```text
+//! # the two rules from the puzzle
+//! sled-rental: count
+//! toboggan: exactly-one-of positions
+//! strict: count and none-of [0-9] and length 8-
+//! ```
+//!
+//! The rules are
+//!
+//! - `count [class] [min-max]`: how many characters are in `class`, within `min-max`. Without a
+//!   class, counts the entry's letter, and without a range, uses the entry's numbers.
+//! - `exactly-one-of positions`, `all-of positions` and `none-of positions`: how many of the two
+//!   positions given by the entry's numbers, counting from 1, hold the entry's letter.
+//! - `none-of [class]` and `only [class]`: whether no characters or all of them are in `class`.
+//! - `length min-max`: how long the password is.
+//!
+//! A class is a list of characters and ranges in brackets, like `[a-fxz]`, or everything but
+//! them with a `^` in front, like `[^0-9]`. A range like `min-max` can leave out either end,
+//! or be a single number for exactly that many.
+//!
+//! A `#` at the start of a line or after a space starts a comment that runs to the end of the
+//! line, unless it is inside a class like `[!#$]`.
+//!
+//! A [`Report`] checks every entry against a policy and says why each failing one failed, so that
+//! the failures can be grouped by kind or exported as CSV.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+use crate::day2::Password;
+use crate::error::{self, Line, Origin, ParseError};
+
+/// A rule for which entries of the database are valid.
+pub trait PasswordPolicy {
+    /// Why `entry` isn't valid, if it isn't.
+    fn check(&self, entry: &Password) -> Result<(), Failure>;
+
+    fn is_valid(&self, entry: &Password) -> bool {
+        self.check(entry).is_ok()
+    }
+}
+
+/// How many entries of `database` are valid under `policy`.
+pub fn count_valid(policy: &dyn PasswordPolicy, database: &[Password]) -> usize {
+    database
+        .iter()
+        .filter(|entry| policy.is_valid(entry))
+        .count()
+}
+
+/// What sort of rule an entry broke, for grouping failures together.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FailureKind {
+    TooFew,
+    TooMany,
+    Positions,
+    Forbidden,
+    NotAllowed,
+    TooShort,
+    TooLong,
+}
+
+impl FailureKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            FailureKind::TooFew => "too-few",
+            FailureKind::TooMany => "too-many",
+            FailureKind::Positions => "positions",
+            FailureKind::Forbidden => "forbidden",
+            FailureKind::NotAllowed => "not-allowed",
+            FailureKind::TooShort => "too-short",
+            FailureKind::TooLong => "too-long",
+        }
+    }
+}
+
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Why an entry isn't valid, in words.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub kind: FailureKind,
+    pub reason: String,
+}
+
+impl Failure {
+    fn new(kind: FailureKind, reason: String) -> Result<(), Failure> {
+        Err(Failure { kind, reason })
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.reason)
+    }
+}
+
+/// The entry's letter appears at least its first number and at most its second number of times.
+pub struct LetterCount;
+
+impl PasswordPolicy for LetterCount {
+    fn check(&self, entry: &Password) -> Result<(), Failure> {
+        Rule::Count(None, None).check(entry)
+    }
+}
+
+/// Exactly one of the positions given by the entry's two numbers, counting from 1, holds its
+/// letter.
+pub struct OnePosition;
+
+impl PasswordPolicy for OnePosition {
+    fn check(&self, entry: &Password) -> Result<(), Failure> {
+        Rule::Positions(Bounds::exactly(1)).check(entry)
+    }
+}
+
+/// An inclusive range of counts, which may be open at the top.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl Bounds {
+    /// The range given by an entry's numbers.
+    pub fn of(entry: &Password) -> Bounds {
+        Bounds {
+            min: entry.lower as usize,
+            max: Some(entry.upper as usize),
+        }
+    }
+
+    pub const fn exactly(n: usize) -> Bounds {
+        Bounds {
+            min: n,
+            max: Some(n),
+        }
+    }
+
+    pub fn contains(self, n: usize) -> bool {
+        self.min <= n && self.max.is_none_or(|max| n <= max)
+    }
+
+    // Whether `n` is too low or too high, as one of the two kinds given.
+    fn check(self, n: usize, kinds: [FailureKind; 2]) -> Result<(), FailureKind> {
+        if n < self.min {
+            Err(kinds[0])
+        } else if self.max.is_some_and(|max| n > max) {
+            Err(kinds[1])
+        } else {
+            Ok(())
+        }
+    }
+
+    fn parse(line: &Line, token: &str) -> Result<Bounds, ParseError> {
+        let expected = "a range like `1-3`, `2-`, `-4` or `5`";
+        let number = |s: &str| line.parse::<usize>(s, expected);
+
+        match token.split_once('-') {
+            Some(("", "")) => Err(line.error(token, expected)),
+            Some((min, max)) => Ok(Bounds {
+                min: if min.is_empty() { 0 } else { number(min)? },
+                max: if max.is_empty() {
+                    None
+                } else {
+                    Some(number(max)?)
+                },
+            }),
+            None => Ok(Bounds::exactly(number(token)?)),
+        }
+    }
+}
+
+impl fmt::Display for Bounds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.min, self.max) {
+            (min, Some(max)) if min == max => write!(f, "exactly {}", min),
+            (min, None) => write!(f, "at least {}", min),
+            (0, Some(max)) => write!(f, "at most {}", max),
+            (min, Some(max)) => write!(f, "{}-{}", min, max),
+        }
+    }
+}
+
+/// A set of characters, written like `[a-z_]` or `[^0-9]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CharClass {
+    negated: bool,
+    ranges: Vec<(char, char)>,
+}
+
+impl CharClass {
+    pub fn contains(&self, c: char) -> bool {
+        self.ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != self.negated
+    }
+
+    fn parse(line: &Line, token: &str) -> Result<CharClass, ParseError> {
+        let inner = token
+            .strip_prefix('[')
+            .and_then(|t| t.strip_suffix(']'))
+            .ok_or_else(|| line.error(token, "a class like `[a-z]`"))?;
+        let (negated, inner) = match inner.strip_prefix('^') {
+            Some(rest) => (true, rest),
+            None => (false, inner),
+        };
+
+        let chars: Vec<char> = inner.chars().collect();
+        if chars.is_empty() {
+            return Err(line.error(token, "a class with at least one character"));
+        }
+
+        let mut ranges = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            if i + 2 < chars.len() && chars[i + 1] == '-' {
+                if chars[i] > chars[i + 2] {
+                    return Err(line.error(token, "a class whose ranges go upwards"));
+                }
+                ranges.push((chars[i], chars[i + 2]));
+                i += 3;
+            } else {
+                ranges.push((chars[i], chars[i]));
+                i += 1;
+            }
+        }
+
+        Ok(CharClass { negated, ranges })
+    }
+}
+
+impl fmt::Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(if self.negated { "[^" } else { "[" })?;
+        for &(lo, hi) in &self.ranges {
+            if lo == hi {
+                write!(f, "{}", lo)?;
+            } else {
+                write!(f, "{}-{}", lo, hi)?;
+            }
+        }
+        f.write_str("]")
+    }
+}
+
+/// One of the rules a [`Policy`] is made of.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// How many characters are in the class, or are the entry's letter if there isn't one,
+    /// within the bounds, or the entry's bounds if there aren't any.
+    Count(Option<CharClass>, Option<Bounds>),
+    /// How many of the entry's positions hold its letter.
+    Positions(Bounds),
+    NoneOf(CharClass),
+    Only(CharClass),
+    Length(Bounds),
+}
+
+fn times(n: usize) -> String {
+    match n {
+        1 => "once".to_string(),
+        n => format!("{} times", n),
+    }
+}
+
+impl PasswordPolicy for Rule {
+    fn check(&self, entry: &Password) -> Result<(), Failure> {
+        let password = &entry.password;
+        let letter = entry.letter;
+        let too_few_or_many = [FailureKind::TooFew, FailureKind::TooMany];
+
+        match self {
+            Rule::Count(class, bounds) => {
+                let bounds = bounds.unwrap_or_else(|| Bounds::of(entry));
+                let (count, what) = match class {
+                    Some(class) => (
+                        password.chars().filter(|&c| class.contains(c)).count(),
+                        format!("characters in {} appear", class),
+                    ),
+                    None => (
+                        password.chars().filter(|&c| c == letter).count(),
+                        format!("letter '{}' appears", letter),
+                    ),
+                };
+
+                bounds.check(count, too_few_or_many).or_else(|kind| {
+                    Failure::new(
+                        kind,
+                        format!("{} {}, allowed {}", what, times(count), bounds),
+                    )
+                })
+            }
+            Rule::Positions(bounds) => {
+                let positions = [entry.lower, entry.upper];
+                let holds = positions
+                    .map(|pos| pos > 0 && password.chars().nth(pos as usize - 1) == Some(letter));
+                let count = holds.iter().filter(|&&h| h).count();
+                if bounds.contains(count) {
+                    return Ok(());
+                }
+
+                let [a, b] = positions;
+                let reason = match holds {
+                    [true, true] => format!("positions {} and {} both contain '{}'", a, b, letter),
+                    [false, false] => {
+                        format!("neither position {} nor {} contains '{}'", a, b, letter)
+                    }
+                    [true, false] => {
+                        format!("position {} contains '{}' but {} doesn't", a, letter, b)
+                    }
+                    [false, true] => {
+                        format!("position {} contains '{}' but {} doesn't", b, letter, a)
+                    }
+                };
+                Failure::new(FailureKind::Positions, reason)
+            }
+            Rule::NoneOf(class) => match password.chars().position(|c| class.contains(c)) {
+                Some(i) => Failure::new(
+                    FailureKind::Forbidden,
+                    format!(
+                        "'{}' at position {} is in {}",
+                        password.chars().nth(i).unwrap(),
+                        i + 1,
+                        class
+                    ),
+                ),
+                None => Ok(()),
+            },
+            Rule::Only(class) => match password.chars().position(|c| !class.contains(c)) {
+                Some(i) => Failure::new(
+                    FailureKind::NotAllowed,
+                    format!(
+                        "'{}' at position {} is not in {}",
+                        password.chars().nth(i).unwrap(),
+                        i + 1,
+                        class
+                    ),
+                ),
+                None => Ok(()),
+            },
+            Rule::Length(bounds) => {
+                let len = password.chars().count();
+                let kinds = [FailureKind::TooShort, FailureKind::TooLong];
+                bounds.check(len, kinds).or_else(|kind| {
+                    Failure::new(kind, format!("{} characters long, allowed {}", len, bounds))
+                })
+            }
+        }
+    }
+}
+
+/// A named list of rules, all of which have to hold.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Policy {
+    pub name: String,
+    pub rules: Vec<Rule>,
+}
+
+/// Fails with the first rule that does.
+impl PasswordPolicy for Policy {
+    fn check(&self, entry: &Password) -> Result<(), Failure> {
+        self.rules.iter().try_for_each(|rule| rule.check(entry))
+    }
+}
+
+fn parse_rule(line: &Line, text: &str) -> Result<Rule, ParseError> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+
+    match tokens[..] {
+        ["count"] => Ok(Rule::Count(None, None)),
+        ["count", arg] if arg.starts_with('[') => {
+            Ok(Rule::Count(Some(CharClass::parse(line, arg)?), None))
+        }
+        ["count", bounds] => Ok(Rule::Count(None, Some(Bounds::parse(line, bounds)?))),
+        ["count", class, bounds] => Ok(Rule::Count(
+            Some(CharClass::parse(line, class)?),
+            Some(Bounds::parse(line, bounds)?),
+        )),
+        ["exactly-one-of", "positions"] => Ok(Rule::Positions(Bounds::exactly(1))),
+        ["all-of", "positions"] => Ok(Rule::Positions(Bounds::exactly(2))),
+        ["none-of", "positions"] => Ok(Rule::Positions(Bounds::exactly(0))),
+        ["none-of", class] => Ok(Rule::NoneOf(CharClass::parse(line, class)?)),
+        ["only", class] => Ok(Rule::Only(CharClass::parse(line, class)?)),
+        ["length", bounds] => Ok(Rule::Length(Bounds::parse(line, bounds)?)),
+        [] => Err(line.end("a rule")),
+        [first, ..] => Err(line.error(
+            first,
+            "`count`, `exactly-one-of`, `all-of`, `none-of`, `only` or `length`, \
+             followed by what they take",
+        )),
+    }
+}
+
+// Cuts `text` off at the `#` that starts a comment, if there is one.
+fn strip_comment(text: &str) -> &str {
+    let mut in_class = false;
+    let mut after_space = true;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '#' if after_space && !in_class => return &text[..i],
+            _ => {}
+        }
+        after_space = c.is_whitespace();
+    }
+    text
+}
+
+/// Parses a config file of policies, skipping blank lines and comments.
+pub fn parse_policies(input: &str) -> Result<Vec<Policy>, ParseError> {
+    let mut policies: Vec<Policy> = Vec::new();
+
+    for line in error::lines_from(Origin::File("policy config"), input) {
+        let text = strip_comment(line.text).trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let (name, rules) = text
+            .split_once(':')
+            .ok_or_else(|| line.error(text, "`name: rules`"))?;
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(line.error(name, "a policy name"));
+        } else if policies.iter().any(|policy| policy.name == name) {
+            return Err(line.error(name, "a policy name that isn't taken yet"));
+        }
+
+        let rules = rules
+            .split(" and ")
+            .map(|rule| parse_rule(&line, rule))
+            .collect::<Result<_, _>>()?;
+        policies.push(Policy {
+            name: name.to_string(),
+            rules,
+        });
+    }
+
+    Ok(policies)
+}
+
+/// How one entry of the database fared under a policy.
+#[derive(Clone, Debug)]
+pub struct Verdict<'a> {
+    pub line: usize,
+    pub entry: &'a Password,
+    pub result: Result<(), Failure>,
+}
+
+/// Every entry of a database checked against a policy, with the reason for each failure.
+#[derive(Clone, Debug)]
+pub struct Report<'a> {
+    pub verdicts: Vec<Verdict<'a>>,
+}
+
+impl<'a> Report<'a> {
+    pub fn new(policy: &dyn PasswordPolicy, database: &'a [Password]) -> Report<'a> {
+        let verdicts = database
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| Verdict {
+                line: i + 1,
+                entry,
+                result: policy.check(entry),
+            })
+            .collect();
+
+        Report { verdicts }
+    }
+
+    pub fn passed(&self) -> usize {
+        self.verdicts.iter().filter(|v| v.result.is_ok()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.verdicts.len() - self.passed()
+    }
+
+    /// The entries that failed, by what sort of rule they broke.
+    pub fn by_kind(&self) -> BTreeMap<FailureKind, Vec<&Verdict<'a>>> {
+        let mut groups: BTreeMap<FailureKind, Vec<&Verdict>> = BTreeMap::new();
+        for verdict in &self.verdicts {
+            if let Err(failure) = &verdict.result {
+                groups.entry(failure.kind).or_default().push(verdict);
+            }
+        }
+        groups
+    }
+
+    /// A line per entry, under a header of `line,entry,result,kind,reason`.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("line,entry,result,kind,reason\n");
+        for verdict in &self.verdicts {
+            let (result, kind, reason) = match &verdict.result {
+                Ok(()) => ("pass", "", String::new()),
+                Err(failure) => ("fail", failure.kind.name(), failure.reason.clone()),
+            };
+            writeln!(
+                out,
+                "{},{},{},{},{}",
+                verdict.line,
+                csv_field(&verdict.entry.to_string()),
+                result,
+                kind,
+                csv_field(&reason)
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+// Quotes a field if it has anything in it that CSV gives a meaning to.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day2;
+    use crate::gen::{self, Params};
+
+    static CONFIG: &str = "\
+# the two rules from the puzzle
+sled-rental: count
+toboggan: exactly-one-of positions
+
+strict: count and none-of [0-9] and length 8-
+lowercase: only [a-z]  # no digits or capitals
+";
+
+    #[test]
+    fn test_builtins_match_the_language() {
+        let policies = parse_policies(CONFIG).unwrap();
+        let input = gen::generate(2, &Params::default()).unwrap();
+        let database = day2::parse(&input).unwrap();
+
+        for entry in &database {
+            assert_eq!(policies[0].is_valid(entry), LetterCount.is_valid(entry));
+            assert_eq!(policies[1].is_valid(entry), OnePosition.is_valid(entry));
+        }
+    }
+
+    #[test]
+    fn test_policies() {
+        let policies = parse_policies(CONFIG).unwrap();
+        let names: Vec<&str> = policies.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["sled-rental", "toboggan", "strict", "lowercase"]);
+
+        let database = day2::parse("1-3 a: abcde\n1-3 a: abcde1234\n2-9 c: Ccccccccc").unwrap();
+        let valid: Vec<usize> = policies
+            .iter()
+            .map(|policy| count_valid(policy, &database))
+            .collect();
+        assert_eq!(valid, [3, 2, 1, 1]);
+
+        // a `#` in a class isn't a comment, but one after a space is
+        let policies = parse_policies("symbols: none-of [!#$] # no symbols").unwrap();
+        let database = day2::parse("1-3 a: abc\n1-3 a: a#c").unwrap();
+        assert_eq!(count_valid(&policies[0], &database), 1);
+
+        let class = CharClass::parse(&error::lines(2, "[^a-cx]").next().unwrap(), "[^a-cx]");
+        let class = class.unwrap();
+        assert!(!class.contains('b') && !class.contains('x') && class.contains('d'));
+    }
+
+    #[test]
+    fn test_reasons() {
+        let database = day2::parse("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc").unwrap();
+        let reasons = |policy: &dyn PasswordPolicy| -> Vec<String> {
+            database
+                .iter()
+                .map(|entry| match policy.check(entry) {
+                    Ok(()) => "ok".to_string(),
+                    Err(failure) => format!("{}: {}", failure.kind, failure),
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            reasons(&LetterCount),
+            [
+                "ok",
+                "too-few: letter 'b' appears 0 times, allowed 1-3",
+                "ok"
+            ]
+        );
+        assert_eq!(
+            reasons(&OnePosition),
+            [
+                "ok",
+                "positions: neither position 1 nor 3 contains 'b'",
+                "positions: positions 2 and 9 both contain 'c'"
+            ]
+        );
+
+        let policies = parse_policies("p: length -5 and only [^d-f] and count [c] 1").unwrap();
+        assert_eq!(
+            reasons(&policies[0]),
+            [
+                "not-allowed: 'd' at position 4 is not in [^d-f]",
+                "not-allowed: 'd' at position 2 is not in [^d-f]",
+                "too-long: 9 characters long, allowed at most 5"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let database = day2::parse("1-3 a: abcde\n1-3 b: cdefg\n1-1 \": a,\"b").unwrap();
+        let report = Report::new(&LetterCount, &database);
+        assert_eq!((report.passed(), report.failed()), (2, 1));
+
+        let groups = report.by_kind();
+        let lines: Vec<usize> = groups[&FailureKind::TooFew]
+            .iter()
+            .map(|v| v.line)
+            .collect();
+        assert_eq!((groups.len(), lines), (1, vec![2]));
+
+        assert_eq!(
+            report.to_csv(),
+            "line,entry,result,kind,reason\n\
+             1,1-3 a: abcde,pass,,\n\
+             2,1-3 b: cdefg,fail,too-few,\"letter 'b' appears 0 times, allowed 1-3\"\n\
+             3,\"1-1 \"\": a,\"\"b\",pass,,\n"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = parse_policies("p: count and lenght 3").unwrap_err();
+        assert_eq!((err.column, err.got.as_str()), (14, "lenght"));
+
+        let err = parse_policies("p: count [a-z] 3-x").unwrap_err();
+        assert_eq!((err.column, err.got.as_str()), (18, "x"));
+
+        let err = parse_policies("p: only [z-a]").unwrap_err();
+        assert_eq!(err.expected, "a class whose ranges go upwards");
+
+        let err = parse_policies("p: count\np: length 3").unwrap_err();
+        assert_eq!(
+            (err.line, err.expected.as_str()),
+            (2, "a policy name that isn't taken yet")
+        );
+        assert!(err
+            .to_string()
+            .starts_with("policy config, line 2, column 1:"));
+    }
+}
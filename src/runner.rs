@@ -0,0 +1,360 @@
+//! Runs many days at once on a pool of threads, collecting every answer, timing and
+//! failure into one report, so that one broken day does not take the rest down with it.
+
+use std::any::Any;
+use std::fmt::Write;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::solution::Day;
+
+/// The outcome of running one part of a day.
+#[derive(Clone, Debug)]
+pub struct PartReport {
+    pub part: u8,
+    pub time: Duration,
+    pub answer: Result<String, String>,
+}
+
+/// The outcome of running a day: its parse time and parts if parsing succeeded,
+/// or why it could not be run at all, starting with the day like a [`ParseError`] does.
+///
+/// [`ParseError`]: crate::ParseError
+#[derive(Clone, Debug)]
+pub struct DayReport {
+    pub day: u8,
+    /// The [`input_hash`] of the input, if it could be read.
+    pub input_hash: Option<u64>,
+    pub parse: Result<Duration, String>,
+    pub parts: Vec<PartReport>,
+}
+
+impl DayReport {
+    pub fn is_ok(&self) -> bool {
+        self.parse.is_ok() && self.parts.iter().all(|part| part.answer.is_ok())
+    }
+}
+
+/// Parses `input` and runs `parts` of `day` on it, turning errors and panics into reports.
+/// Parts the day doesn't have are left out.
+pub fn run_day(day: &Day, input: io::Result<String>, parts: &[u8]) -> DayReport {
+    let input_hash = input.as_ref().ok().map(|input| input_hash(input));
+
+    let start = Instant::now();
+    // parse errors already say which day they are from, so the other errors follow suit
+    let parsed = input
+        .map_err(|err| format!("day {}: could not read the input: {}", day.day, err))
+        .and_then(|input| {
+            catch(|| day.parse(&input))
+                .map_err(|msg| format!("day {}: {}", day.day, msg))?
+                .map_err(|err| err.to_string())
+        });
+    let parse = start.elapsed();
+
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            return DayReport {
+                day: day.day,
+                input_hash,
+                parse: Err(err),
+                parts: Vec::new(),
+            }
+        }
+    };
+
+    let parts = parts
+        .iter()
+        .filter(|&&part| part <= day.parts)
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = catch(|| match part {
+                1 => parsed.part1(),
+                _ => parsed.part2(),
+            })
+            .and_then(|answer| answer);
+
+            PartReport {
+                part,
+                time: start.elapsed(),
+                answer,
+            }
+        })
+        .collect();
+
+    DayReport {
+        day: day.day,
+        input_hash,
+        parse: Ok(parse),
+        parts,
+    }
+}
+
+/// Runs every day in `days` on `threads` worker threads, reading each day's input with `load`.
+/// The reports come back in the same order as `days`. Panics still go through the panic hook as
+/// they happen, which is up to the caller to silence if it wants to.
+pub fn run_parallel(
+    days: &[Day],
+    parts: &[u8],
+    threads: usize,
+    load: impl Fn(&Day) -> io::Result<String> + Sync,
+) -> Vec<DayReport> {
+    let next = AtomicUsize::new(0);
+    let reports = Mutex::new(vec![None; days.len()]);
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, days.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let day = match days.get(i) {
+                    Some(day) => day,
+                    None => break,
+                };
+
+                let report = run_day(day, load(day), parts);
+                reports.lock().unwrap()[i] = Some(report);
+            });
+        }
+    });
+
+    reports
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|report| report.expect("every day is run by some worker"))
+        .collect()
+}
+
+fn catch<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(&*payload))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let msg = if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg
+    } else {
+        "<unknown>"
+    };
+
+    format!("panicked: {}", msg)
+}
+
+/// Lays the reports out as a table with one row per day, followed by any failures.
+pub fn table(reports: &[DayReport]) -> String {
+    let widest = reports
+        .iter()
+        .map(|report| &report.parts)
+        .max_by_key(|parts| parts.len());
+    let mut out = format!("{:>3}  {:>10}", "day", "parse");
+    for part in widest.into_iter().flatten() {
+        write!(out, "  {:<20}{:>10}", format!("part {}", part.part), "time").unwrap();
+    }
+    out.push('\n');
+
+    let mut failures = String::new();
+    let mut total = Duration::default();
+
+    for report in reports {
+        let parse = match &report.parse {
+            Ok(time) => {
+                total += *time;
+                format!("{:.2?}", time)
+            }
+            Err(err) => {
+                writeln!(failures, "{}", err).unwrap();
+                "failed".to_string()
+            }
+        };
+        write!(out, "{:>3}  {:>10}", report.day, parse).unwrap();
+
+        for part in &report.parts {
+            total += part.time;
+            let answer = match &part.answer {
+                Ok(answer) => answer.as_str(),
+                Err(err) => {
+                    writeln!(failures, "day {} part {}: {}", report.day, part.part, err).unwrap();
+                    "failed"
+                }
+            };
+            write!(out, "  {:<20}{:>10.2?}", answer, part.time).unwrap();
+        }
+        out.push('\n');
+    }
+
+    writeln!(out, "total time spent: {:.2?}", total).unwrap();
+    if !failures.is_empty() {
+        write!(out, "\nfailures:\n{}", failures).unwrap();
+    }
+    out
+}
+
+/// Serializes the reports as a JSON array of flat records, one per line: one for every part
+/// that was run, or a single one with a null `part` for a day that could not be parsed.
+/// Answers are always strings, so that big integers and custom outputs come through the same,
+/// and times are in nanoseconds.
+pub fn to_json(reports: &[DayReport]) -> String {
+    let mut records = Vec::new();
+
+    for report in reports {
+        let input_hash = report
+            .input_hash
+            .map_or_else(|| "null".to_string(), |hash| format!("\"{:016x}\"", hash));
+        let (parse, parse_error) = match &report.parse {
+            Ok(time) => (time.as_nanos().to_string(), None),
+            Err(err) => ("null".to_string(), Some(err)),
+        };
+
+        let mut record = |part: String, answer: String, error: String, solve: String| {
+            records.push(format!(
+                r#"{{"day":{},"part":{},"answer":{},"error":{},"parse_ns":{},"solve_ns":{},"input_hash":{}}}"#,
+                report.day, part, answer, error, parse, solve, input_hash
+            ))
+        };
+
+        if let Some(err) = parse_error {
+            record(
+                "null".into(),
+                "null".into(),
+                json_string(err),
+                "null".into(),
+            );
+        }
+        for part in &report.parts {
+            let (answer, error) = match &part.answer {
+                Ok(answer) => (json_string(answer), "null".to_string()),
+                Err(err) => ("null".to_string(), json_string(err)),
+            };
+            record(
+                part.part.to_string(),
+                answer,
+                error,
+                part.time.as_nanos().to_string(),
+            );
+        }
+    }
+
+    format!("[\n{}\n]", records.join(",\n"))
+}
+
+/// A 64-bit FNV-1a hash of an input, to tell which input a result came from.
+/// Unlike `DefaultHasher` it is the same on every platform and Rust version.
+pub fn input_hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+    use crate::solution::Solution;
+
+    struct Fragile;
+
+    impl Solution for Fragile {
+        const DAY: u8 = 99;
+
+        type Input = u32;
+        type Output1 = u32;
+        type Output2 = u32;
+
+        fn parse(input: &str) -> Result<u32, ParseError> {
+            let line = crate::error::lines(99, input).next().unwrap();
+            line.parse(line.text, "a number")
+        }
+
+        fn part1(input: &u32) -> u32 {
+            input * 2
+        }
+
+        fn part2(input: &u32) -> u32 {
+            assert!(*input < 10, "too big");
+            input + 1
+        }
+    }
+
+    #[test]
+    fn test_failures_are_isolated() {
+        let days = [Day::new::<Fragile>(); 4];
+        let inputs = Mutex::new(vec!["x", "40", "4"]);
+        let load = |_: &Day| match inputs.lock().unwrap().pop() {
+            Some(input) => Ok(input.to_string()),
+            None => Err(io::Error::new(io::ErrorKind::NotFound, "missing")),
+        };
+
+        // which day gets which input depends on the scheduling, so only compare the outcomes
+        let mut outcomes: Vec<_> = run_parallel(&days, &[1, 2], 3, load)
+            .into_iter()
+            .map(|report| {
+                let answers: Vec<_> = report.parts.into_iter().map(|p| p.answer).collect();
+                (report.parse.map(|_| ()), answers)
+            })
+            .collect();
+        outcomes.sort();
+
+        assert_eq!(
+            outcomes,
+            [
+                (Ok(()), vec![Ok("8".to_string()), Ok("5".to_string())]),
+                (
+                    Ok(()),
+                    vec![Ok("80".to_string()), Err("panicked: too big".to_string())]
+                ),
+                (
+                    Err("day 99, line 1, column 1: expected a number, got `x`".to_string()),
+                    vec![]
+                ),
+                (
+                    Err("day 99: could not read the input: missing".to_string()),
+                    vec![]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(json_string("a \"b\"\n\\"), r#""a \"b\"\n\\""#);
+        assert_eq!(input_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(input_hash("a"), 0xaf63_dc4c_8601_ec8c);
+
+        let day = Day::new::<Fragile>();
+        let reports = [
+            run_day(&day, Ok("40".to_string()), &[1, 2]),
+            run_day(&day, Ok("x".to_string()), &[1, 2]),
+        ];
+        let json: serde_json::Value = serde_json::from_str(&to_json(&reports)).unwrap();
+        let records = json.as_array().unwrap();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0]["answer"], "80");
+        assert_eq!(records[1]["error"], "panicked: too big");
+        assert_eq!(
+            records[1]["input_hash"],
+            format!("{:016x}", input_hash("40"))
+        );
+        assert!(records[2]["part"].is_null() && records[2]["parse_ns"].is_null());
+    }
+}
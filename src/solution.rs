@@ -0,0 +1,132 @@
+use std::any::Any;
+use std::convert::Infallible;
+use std::fmt::Display;
+
+use crate::error::ParseError;
+
+/// One day's puzzle: parsing its input, and solving both of its parts.
+pub trait Solution {
+    const DAY: u8;
+    /// How many parts the puzzle has. Day 25 only has one, so its `part2` is never called.
+    const PARTS: u8 = 2;
+
+    type Input: 'static;
+    type Output1: Answer;
+    type Output2: Answer;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Output1;
+    fn part2(input: &Self::Input) -> Self::Output2;
+}
+
+/// What a part can return: an answer to show, or a `Result` whose error says why there is no
+/// answer for this input.
+pub trait Answer {
+    fn into_answer(self) -> Result<String, String>;
+}
+
+macro_rules! display_answers {
+    ($($ty:ty),*) => {
+        $(impl Answer for $ty {
+            fn into_answer(self) -> Result<String, String> {
+                Ok(self.to_string())
+            }
+        })*
+    };
+}
+
+display_answers!(i32, i64, u32, u64, usize, String, Infallible);
+
+impl<T: Answer, E: Display> Answer for Result<T, E> {
+    fn into_answer(self) -> Result<String, String> {
+        self.map_err(|err| err.to_string())?.into_answer()
+    }
+}
+
+/// A type-erased [`Solution`], so that days with different input and output
+/// types can be listed together and run without knowing them.
+#[derive(Copy, Clone)]
+pub struct Day {
+    pub day: u8,
+    pub parts: u8,
+    parse: fn(&str) -> Result<Box<dyn Any>, ParseError>,
+    part1: fn(&dyn Any) -> Result<String, String>,
+    part2: fn(&dyn Any) -> Result<String, String>,
+}
+
+impl Day {
+    pub const fn new<S: Solution>() -> Day {
+        Day {
+            day: S::DAY,
+            parts: S::PARTS,
+            parse: parse_erased::<S>,
+            part1: part1_erased::<S>,
+            part2: part2_erased::<S>,
+        }
+    }
+
+    /// Where `cargo aoc` keeps this day's puzzle input, relative to the crate root.
+    pub fn input_path(&self) -> String {
+        format!("input/2020/day{}.txt", self.day)
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Parsed, ParseError> {
+        Ok(Parsed {
+            day: *self,
+            input: (self.parse)(input)?,
+        })
+    }
+}
+
+impl std::fmt::Debug for Day {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Day").field("day", &self.day).finish()
+    }
+}
+
+/// A day's parsed input, ready to have either part run on it.
+pub struct Parsed {
+    day: Day,
+    input: Box<dyn Any>,
+}
+
+/// Each part gives back its answer, or why there isn't one.
+impl Parsed {
+    pub fn part1(&self) -> Result<String, String> {
+        (self.day.part1)(&*self.input)
+    }
+
+    pub fn part2(&self) -> Result<String, String> {
+        (self.day.part2)(&*self.input)
+    }
+}
+
+fn parse_erased<S: Solution>(input: &str) -> Result<Box<dyn Any>, ParseError> {
+    Ok(Box::new(S::parse(input)?))
+}
+
+fn part1_erased<S: Solution>(input: &dyn Any) -> Result<String, String> {
+    S::part1(downcast::<S>(input)).into_answer()
+}
+
+fn part2_erased<S: Solution>(input: &dyn Any) -> Result<String, String> {
+    S::part2(downcast::<S>(input)).into_answer()
+}
+
+fn downcast<S: Solution>(input: &dyn Any) -> &S::Input {
+    input
+        .downcast_ref()
+        .expect("input was parsed by a different day")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DAYS;
+
+    #[test]
+    fn test_days_in_order() {
+        for (i, day) in DAYS.iter().enumerate() {
+            assert_eq!(day.day as usize, i + 1);
+        }
+    }
+}
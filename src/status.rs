@@ -0,0 +1,117 @@
+//! Which parts of the year are implemented, measured against the puzzles there are.
+
+use std::fmt::Write;
+
+use crate::solution::Day;
+
+/// How many days of the year have a puzzle.
+pub const DAYS_IN_YEAR: u8 = 25;
+
+/// How many parts the puzzle on `day` has: two, except on the last day.
+pub fn parts_of(day: u8) -> u8 {
+    if day == DAYS_IN_YEAR {
+        1
+    } else {
+        2
+    }
+}
+
+/// How many parts a day's puzzle has, and how many of them are implemented.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DayStatus {
+    pub day: u8,
+    pub parts: u8,
+    pub implemented: u8,
+}
+
+impl DayStatus {
+    pub fn is_complete(&self) -> bool {
+        self.implemented >= self.parts
+    }
+}
+
+/// The status of every day of the year, given the days that are implemented.
+pub fn status(days: &[Day]) -> Vec<DayStatus> {
+    (1..=DAYS_IN_YEAR)
+        .map(|day| DayStatus {
+            day,
+            parts: parts_of(day),
+            implemented: days
+                .iter()
+                .find(|d| d.day == day)
+                .map_or(0, |d| d.parts.min(parts_of(day))),
+        })
+        .collect()
+}
+
+/// A line per day saying which parts are done or missing, and a total at the end.
+pub fn table(status: &[DayStatus]) -> String {
+    let mut out = String::from("day  part 1   part 2\n");
+    for day in status {
+        write!(out, "{:>3}", day.day).unwrap();
+        for part in 1..=day.parts {
+            let mark = if part <= day.implemented {
+                "done"
+            } else {
+                "missing"
+            };
+            write!(out, "  {:<7}", mark).unwrap();
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+    }
+
+    let implemented: usize = status.iter().map(|day| day.implemented as usize).sum();
+    let parts: usize = status.iter().map(|day| day.parts as usize).sum();
+    write!(out, "{}/{} parts implemented", implemented, parts).unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DAYS;
+
+    #[test]
+    fn test_year_is_complete() {
+        let status = status(DAYS);
+        let parts: usize = status.iter().map(|day| day.parts as usize).sum();
+        assert_eq!(parts, 49);
+
+        let missing: Vec<String> = status
+            .iter()
+            .filter(|day| !day.is_complete())
+            .map(|day| format!("day {}", day.day))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "missing parts on {}",
+            missing.join(", ")
+        );
+    }
+
+    #[test]
+    fn test_registered_days_have_every_part() {
+        for day in DAYS {
+            assert!((1..=DAYS_IN_YEAR).contains(&day.day), "day {}", day.day);
+            assert_eq!(
+                day.parts,
+                parts_of(day.day),
+                "day {} is missing a part",
+                day.day
+            );
+        }
+    }
+
+    #[test]
+    fn test_table() {
+        let status = status(&[DAYS[0], DAYS[24]]);
+        let table = table(&status);
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines[1], "  1  done     done");
+        assert_eq!(lines[2], "  2  missing  missing");
+        assert_eq!(lines[25], " 25  done");
+        assert_eq!(lines[26], "3/49 parts implemented");
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::hash_map::{Entry, HashMap};
+use std::fs;
+use std::path::Path;
+
+use advent_of_code_2020::{Parsed, DAYS};
+use toml::Value;
+
+struct Answer {
+    day: u8,
+    part: u8,
+    input: String,
+    expected: String,
+}
+
+struct Manifest {
+    answers: Vec<Answer>,
+    /// Days that have no checked-in input, and so no answers to check.
+    skipped: Vec<u8>,
+}
+
+fn load_manifest(root: &Path) -> Manifest {
+    let manifest = fs::read_to_string(root.join("tests/answers.toml"))
+        .expect("could not read tests/answers.toml");
+    let manifest: Value = manifest
+        .parse()
+        .expect("tests/answers.toml is not valid TOML");
+
+    let skipped = manifest
+        .get("skipped")
+        .and_then(Value::as_array)
+        .expect("tests/answers.toml has no `skipped` list")
+        .iter()
+        .map(|day| day.as_integer().expect("`skipped` must list day numbers") as u8)
+        .collect();
+
+    let entries = manifest
+        .get("answer")
+        .and_then(Value::as_array)
+        .expect("tests/answers.toml has no [[answer]] entries");
+
+    let answers = entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let field = |name: &str| {
+                entry
+                    .get(name)
+                    .unwrap_or_else(|| panic!("answer #{} is missing `{}`", i + 1, name))
+            };
+            let number = |name: &str| {
+                field(name)
+                    .as_integer()
+                    .unwrap_or_else(|| panic!("answer #{}: `{}` must be an integer", i + 1, name))
+                    as u8
+            };
+            let string = |name: &str| {
+                field(name)
+                    .as_str()
+                    .unwrap_or_else(|| panic!("answer #{}: `{}` must be a string", i + 1, name))
+                    .to_string()
+            };
+
+            Answer {
+                day: number("day"),
+                part: number("part"),
+                input: string("input"),
+                expected: string("expected"),
+            }
+        })
+        .collect();
+
+    Manifest { answers, skipped }
+}
+
+#[test]
+fn test_answers() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut parsed: HashMap<(u8, &str), Parsed> = HashMap::new();
+    let mut failures = Vec::new();
+
+    let manifest = load_manifest(root);
+    for answer in &manifest.answers {
+        let name = format!("day {} part {} ({})", answer.day, answer.part, answer.input);
+
+        let day = match DAYS.iter().find(|day| day.day == answer.day) {
+            Some(day) => day,
+            None => {
+                failures.push(format!("{}: day is not registered", name));
+                continue;
+            }
+        };
+
+        let parsed = match parsed.entry((answer.day, &answer.input)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let input = fs::read_to_string(root.join(&answer.input))
+                    .unwrap_or_else(|err| panic!("could not read {}: {}", answer.input, err));
+
+                match day.parse(&input) {
+                    Ok(input) => entry.insert(input),
+                    Err(err) => {
+                        failures.push(format!("{}: {}", name, err));
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let got = match answer.part {
+            1 => parsed.part1(),
+            2 if day.parts == 2 => parsed.part2(),
+            part => {
+                failures.push(format!("{}: there is no part {}", name, part));
+                continue;
+            }
+        };
+
+        match got {
+            Ok(got) if got == answer.expected => {}
+            Ok(got) => failures.push(format!(
+                "{}: expected `{}`, got `{}`",
+                name, answer.expected, got
+            )),
+            Err(err) => failures.push(format!("{}: {}", name, err)),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} answers did not match:\n{}",
+        failures.len(),
+        manifest.answers.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_every_day_is_covered_or_skipped() {
+    let manifest = load_manifest(Path::new(env!("CARGO_MANIFEST_DIR")));
+
+    for day in DAYS {
+        let answers = manifest.answers.iter().filter(|a| a.day == day.day).count();
+        let skipped = manifest.skipped.contains(&day.day);
+
+        if skipped {
+            assert_eq!(
+                answers, 0,
+                "day {} has answers but is also skipped",
+                day.day
+            );
+        } else {
+            assert_eq!(
+                answers, day.parts as usize,
+                "day {} needs an answer for each part, or to be listed as skipped",
+                day.day
+            );
+        }
+    }
+
+    let skipped: Vec<String> = manifest.skipped.iter().map(u8::to_string).collect();
+    println!("skipped days without inputs: {}", skipped.join(", "));
+}